@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+
+use crate::event_handler::{EventResults, Swimmer};
+use crate::output::{csv_writer, include_swimmer, include_team, OutputOptions};
+use crate::relay_handler::{RelayResults, RelaySwimmer, RelayTeam};
+use crate::utils::{name_last_first, session_label};
+use crate::ParsedResults;
+
+// ============================================================================
+// DATA STRUCTURES
+// ============================================================================
+
+/// One swim by an athlete: an individual result or a relay leg
+#[derive(Debug, Clone, Copy)]
+pub enum AthleteSwim<'a> {
+    Individual {
+        event: &'a EventResults,
+        swimmer: &'a Swimmer,
+    },
+    RelayLeg {
+        event: &'a RelayResults,
+        team: &'a RelayTeam,
+        /// Leg number, 1-4
+        leg: usize,
+    },
+}
+
+/// All of one athlete's swims across a meet
+#[derive(Debug, Clone)]
+pub struct Athlete<'a> {
+    /// Name as "Last, First"
+    pub name: String,
+    pub school: String,
+    pub swims: Vec<AthleteSwim<'a>>,
+}
+
+/// Key matching an athlete across events: name in "last, first" form and school, both
+/// lowercased with whitespace collapsed
+pub type AthleteKey = (String, String);
+
+// ============================================================================
+// AGGREGATION
+// ============================================================================
+
+/// Normalizes a name or school for matching
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+impl ParsedResults {
+    /// Groups every individual swim and relay leg by athlete (name and school), matching
+    /// names given as "First Last" or "Last, First"
+    pub fn athletes(&self) -> BTreeMap<AthleteKey, Athlete<'_>> {
+        let mut athletes: BTreeMap<AthleteKey, Athlete> = BTreeMap::new();
+        let mut add = |name: &str, school: &str, swim| {
+            let name = name_last_first(name.trim());
+            let key = (normalize(&name), normalize(school));
+            athletes.entry(key)
+                .or_insert_with(|| Athlete { name, school: school.trim().to_string(), swims: Vec::new() })
+                .swims
+                .push(swim);
+        };
+
+        for event in &self.individual_results {
+            for swimmer in &event.swimmers {
+                add(&swimmer.name, &swimmer.school, AthleteSwim::Individual { event, swimmer });
+            }
+        }
+        for event in &self.relay_results {
+            for team in &event.teams {
+                let school = team.team_name.as_str();
+                for (i, swimmer) in team.legs().iter().enumerate() {
+                    if swimmer.name.is_empty() {
+                        continue;
+                    }
+                    add(&swimmer.name, school, AthleteSwim::RelayLeg { event, team, leg: i + 1 });
+                }
+            }
+        }
+
+        athletes
+    }
+}
+
+// ============================================================================
+// ATHLETES CSV OUTPUT
+// ============================================================================
+
+/// Writes one row per athlete swim (individual swims and relay legs) with its event
+/// context and the athlete's total swim count, honoring top_n and the team filter
+pub fn write_athletes_csv(
+    results: &ParsedResults,
+    options: &OutputOptions,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv_writer(options).from_path(path)?;
+    writer.write_record([
+        "name", "school", "swim_count", "event_name", "session", "event_number",
+        "swim_type", "leg", "team_name", "place", "final_time", "reaction_time",
+    ])?;
+
+    for athlete in results.athletes().values() {
+        let swims: Vec<&AthleteSwim> = athlete.swims.iter()
+            .filter(|swim| match swim {
+                AthleteSwim::Individual { swimmer, .. } => include_swimmer(swimmer, options),
+                AthleteSwim::RelayLeg { team, .. } => include_team(team, options),
+            })
+            .collect();
+
+        for swim in &swims {
+            let (event_name, session, race_info) = match swim {
+                AthleteSwim::Individual { event, .. } => (&event.event_name, event.session, event.race_info.as_ref()),
+                AthleteSwim::RelayLeg { event, .. } => (&event.event_name, event.session, event.race_info.as_ref()),
+            };
+            let event_number = race_info.map(|i| i.event_number.to_string()).unwrap_or_default();
+
+            let (swim_type, leg, team_name, place, final_time, reaction_time) = match swim {
+                AthleteSwim::Individual { swimmer, .. } => (
+                    "individual",
+                    String::new(),
+                    String::new(),
+                    swimmer.place,
+                    swimmer.final_time.clone(),
+                    swimmer.reaction_time.clone(),
+                ),
+                AthleteSwim::RelayLeg { team, leg, .. } => (
+                    "relay",
+                    leg.to_string(),
+                    team.team_name.clone(),
+                    team.place,
+                    team.final_time.clone(),
+                    team.swimmers.get(leg - 1).and_then(|s: &RelaySwimmer| s.reaction_time.clone()),
+                ),
+            };
+
+            writer.write_record([
+                options.name_format.apply(&athlete.name),
+                athlete.school.clone(),
+                swims.len().to_string(),
+                event_name.clone(),
+                session_label(session).to_string(),
+                event_number,
+                swim_type.to_string(),
+                leg,
+                team_name,
+                place.map(|p| p.to_string()).unwrap_or_default(),
+                options.time_format.apply(&final_time),
+                reaction_time.unwrap_or_default(),
+            ])?;
+        }
+    }
+
+    writer.flush()?;
+    log::info!("Athletes written to {}", path.display());
+    Ok(())
+}
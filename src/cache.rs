@@ -0,0 +1,118 @@
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::http_client::Validators;
+
+/// Counter for unique temp file names across concurrent writes
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// ============================================================================
+// HTML CACHE
+// ============================================================================
+
+/// On-disk cache of fetched pages, stored as `dir/<sha256-of-url>.html`
+#[derive(Debug, Clone)]
+pub struct HtmlCache {
+    pub dir: PathBuf,
+    /// Ignore cached pages and re-download (fresh pages are still written)
+    pub refresh: bool,
+    /// Cached pages older than this are re-downloaded (None = never expire)
+    pub max_age: Option<Duration>,
+}
+
+impl HtmlCache {
+    /// Creates a cache in the given directory with no expiry
+    pub fn new(dir: impl Into<PathBuf>) -> HtmlCache {
+        HtmlCache {
+            dir: dir.into(),
+            refresh: false,
+            max_age: None,
+        }
+    }
+
+    /// Returns the cache file path for a URL
+    pub fn path_for(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.html", url_hash(url)))
+    }
+
+    /// Returns the cached page if present, fresh, and refresh isn't forced
+    pub fn read(&self, url: &str) -> Option<String> {
+        if self.refresh {
+            return None;
+        }
+
+        let path = self.path_for(url);
+        if let Some(max_age) = self.max_age {
+            let modified = fs::metadata(&path).ok()?.modified().ok()?;
+            if modified.elapsed().ok().is_none_or(|age| age > max_age) {
+                return None;
+            }
+        }
+
+        fs::read_to_string(path).ok()
+    }
+
+    /// Writes a page by renaming a temp file into place, so concurrent
+    /// readers never see a partially written page
+    pub fn write(&self, url: &str, body: &str) -> io::Result<()> {
+        self.write_atomic(url, &self.path_for(url), body)
+    }
+
+    /// Returns the ETag/Last-Modified validators saved next to a cached page
+    pub fn read_validators(&self, url: &str) -> Option<Validators> {
+        let content = fs::read_to_string(self.validators_path_for(url)).ok()?;
+        let mut validators = Validators::default();
+        for line in content.lines() {
+            match line.split_once(": ") {
+                Some(("etag", value)) => validators.etag = Some(value.to_string()),
+                Some(("last-modified", value)) => validators.last_modified = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        Some(validators)
+    }
+
+    /// Saves the ETag/Last-Modified validators next to a cached page
+    pub fn write_validators(&self, url: &str, validators: &Validators) -> io::Result<()> {
+        let mut content = String::new();
+        if let Some(ref etag) = validators.etag {
+            content.push_str(&format!("etag: {}\n", etag));
+        }
+        if let Some(ref last_modified) = validators.last_modified {
+            content.push_str(&format!("last-modified: {}\n", last_modified));
+        }
+        self.write_atomic(url, &self.validators_path_for(url), &content)
+    }
+
+    fn validators_path_for(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.validators", url_hash(url)))
+    }
+
+    /// Writes to a temp file in the cache directory, then renames it into place
+    fn write_atomic(&self, url: &str, path: &Path, contents: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let temp_path = self.dir.join(format!(
+            ".{}.{}.{}.tmp",
+            url_hash(url),
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+        ));
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })
+    }
+}
+
+/// Returns the hex SHA-256 of a URL
+fn url_hash(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,294 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::event_handler::{EventResults, Swimmer};
+use crate::relay_handler::{RelayResults, RelayTeam};
+use crate::utils::{name_last_first, session_label};
+use crate::ParsedResults;
+
+// ============================================================================
+// DATA STRUCTURES
+// ============================================================================
+
+/// Differences between two parses of the same meet
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResultsDiff {
+    /// Events only in the new results, as "Event name (Session)"
+    pub added_events: Vec<String>,
+    /// Events only in the old results
+    pub removed_events: Vec<String>,
+    /// Events in both whose entries differ
+    pub changed_events: Vec<EventDiff>,
+}
+
+/// Entry changes within one event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventDiff {
+    pub event_name: String,
+    pub event_number: Option<u32>,
+    pub session: char,
+    /// Swimmers or teams only in the new results, as "Name (School)" or the team name
+    pub added: Vec<String>,
+    /// Swimmers or teams only in the old results
+    pub removed: Vec<String>,
+    pub modified: Vec<EntryChange>,
+}
+
+/// One changed field of a swimmer or team present in both results
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntryChange {
+    pub entry: String,
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+impl ResultsDiff {
+    /// True if the two results match
+    pub fn is_empty(&self) -> bool {
+        self.added_events.is_empty() && self.removed_events.is_empty() && self.changed_events.is_empty()
+    }
+}
+
+// ============================================================================
+// COMPARISON
+// ============================================================================
+
+/// A swimmer or team with its matching key and the fields compared between parses
+struct DiffEntry {
+    label: String,
+    key: String,
+    fields: Vec<(&'static str, String)>,
+}
+
+/// An event's identity and entries
+struct DiffEvent {
+    event_name: String,
+    event_number: Option<u32>,
+    gender: Option<String>,
+    session: char,
+    entries: Vec<DiffEntry>,
+}
+
+/// (is relay, event number or name, gender, session)
+type EventKey = (bool, String, Option<String>, char);
+
+/// Events are matched by number, gender, and session, or by name when the number is
+/// unknown; men's and women's events often share a number
+fn event_key(event: &DiffEvent, is_relay: bool) -> EventKey {
+    let id = match event.event_number {
+        Some(number) => number.to_string(),
+        None => event.event_name.trim().to_lowercase(),
+    };
+    let gender = event.gender.as_ref().map(|g| g.to_lowercase());
+    (is_relay, id, gender, event.session)
+}
+
+/// Lowercased with whitespace collapsed, for matching names and schools
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn swimmer_fields(swimmer: &Swimmer) -> Vec<(&'static str, String)> {
+    vec![
+        ("place", swimmer.place.map(|p| p.to_string()).unwrap_or_default()),
+        ("seed_time", swimmer.seed_time.clone().unwrap_or_default()),
+        ("final_time", swimmer.final_time.clone()),
+        ("dq_description", swimmer.dq_description.clone().unwrap_or_default()),
+    ]
+}
+
+fn team_fields(team: &RelayTeam) -> Vec<(&'static str, String)> {
+    let swimmers: Vec<&str> = team.swimmers.iter().map(|s| s.name.as_str()).collect();
+    vec![
+        ("place", team.place.map(|p| p.to_string()).unwrap_or_default()),
+        ("seed_time", team.seed_time.clone().unwrap_or_default()),
+        ("final_time", team.final_time.clone()),
+        ("dq_description", team.dq_description.clone().unwrap_or_default()),
+        ("swimmers", swimmers.join("; ")),
+    ]
+}
+
+fn individual_event(event: &EventResults) -> DiffEvent {
+    DiffEvent {
+        event_name: event.event_name.clone(),
+        event_number: event.race_info.as_ref().map(|i| i.event_number),
+        gender: event.race_info.as_ref().and_then(|i| i.gender.clone()),
+        session: event.session,
+        entries: event.swimmers.iter()
+            .map(|s| DiffEntry {
+                label: format!("{} ({})", s.name, s.school),
+                key: format!("{}|{}", normalize(&name_last_first(&s.name)), normalize(&s.school)),
+                fields: swimmer_fields(s),
+            })
+            .collect(),
+    }
+}
+
+fn relay_event(event: &RelayResults) -> DiffEvent {
+    DiffEvent {
+        event_name: event.event_name.clone(),
+        event_number: event.race_info.as_ref().map(|i| i.event_number),
+        gender: event.race_info.as_ref().and_then(|i| i.gender.clone()),
+        session: event.session,
+        entries: event.teams.iter()
+            .map(|t| {
+                let label = match t.relay_designation {
+                    Some(designation) => format!("{} {}", t.team_name, designation),
+                    None => t.team_name.clone(),
+                };
+                DiffEntry { key: normalize(&label), label, fields: team_fields(t) }
+            })
+            .collect(),
+    }
+}
+
+fn event_label(event: &DiffEvent) -> String {
+    format!("{} ({})", event.event_name.trim(), session_label(event.session))
+}
+
+/// Compares the entries of one event present in both results
+fn diff_event(old: &DiffEvent, new: &DiffEvent) -> EventDiff {
+    let old_entries: HashMap<&str, &DiffEntry> = old.entries.iter().map(|e| (e.key.as_str(), e)).collect();
+    let new_keys: HashSet<&str> = new.entries.iter().map(|e| e.key.as_str()).collect();
+
+    let mut diff = EventDiff {
+        event_name: new.event_name.clone(),
+        event_number: new.event_number,
+        session: new.session,
+        added: Vec::new(),
+        removed: Vec::new(),
+        modified: Vec::new(),
+    };
+
+    for entry in &new.entries {
+        let Some(old_entry) = old_entries.get(entry.key.as_str()) else {
+            diff.added.push(entry.label.clone());
+            continue;
+        };
+        for ((field, old_value), (_, new_value)) in old_entry.fields.iter().zip(&entry.fields) {
+            if old_value != new_value {
+                diff.modified.push(EntryChange {
+                    entry: entry.label.clone(),
+                    field: *field,
+                    old: old_value.clone(),
+                    new: new_value.clone(),
+                });
+            }
+        }
+    }
+    diff.removed = old.entries.iter()
+        .filter(|e| !new_keys.contains(e.key.as_str()))
+        .map(|e| e.label.clone())
+        .collect();
+
+    diff
+}
+
+/// Compares two parses of a meet, matching events by number and session and swimmers by
+/// name and school (relay teams by team name), and reports added, removed, and modified
+/// entries with their old and new values
+pub fn diff_results(old: &ParsedResults, new: &ParsedResults) -> ResultsDiff {
+    let collect = |results: &ParsedResults| -> Vec<(EventKey, DiffEvent)> {
+        let individual = results.individual_results.iter()
+            .map(individual_event)
+            .map(|e| (event_key(&e, false), e));
+        let relay = results.relay_results.iter()
+            .map(relay_event)
+            .map(|e| (event_key(&e, true), e));
+        individual.chain(relay).collect()
+    };
+    let old_events = collect(old);
+    let new_events = collect(new);
+    let old_by_key: HashMap<&EventKey, &DiffEvent> = old_events.iter().map(|(k, e)| (k, e)).collect();
+    let new_keys: HashSet<&EventKey> = new_events.iter().map(|(k, _)| k).collect();
+
+    let mut diff = ResultsDiff::default();
+    for (key, event) in &new_events {
+        match old_by_key.get(key) {
+            Some(old_event) => {
+                let event_diff = diff_event(old_event, event);
+                if !event_diff.added.is_empty() || !event_diff.removed.is_empty() || !event_diff.modified.is_empty() {
+                    diff.changed_events.push(event_diff);
+                }
+            }
+            None => diff.added_events.push(event_label(event)),
+        }
+    }
+    diff.removed_events = old_events.iter()
+        .filter(|(key, _)| !new_keys.contains(key))
+        .map(|(_, event)| event_label(event))
+        .collect();
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::parse_race_info;
+
+    fn swimmer(place: u8, name: &str, school: &str, final_time: &str) -> Swimmer {
+        Swimmer {
+            place: Some(place),
+            tied: false,
+            name: name.to_string(),
+            year: "SR".to_string(),
+            school: school.to_string(),
+            seed_time: None,
+            final_time: final_time.to_string(),
+            reaction_time: None,
+            dq_description: None,
+            standards: Vec::new(),
+            classification: None,
+            final_type: None,
+            splits: Vec::new(),
+            prelim_splits: Vec::new(),
+        }
+    }
+
+    fn event(headline: &str, swimmers: Vec<Swimmer>) -> EventResults {
+        EventResults {
+            event_name: headline.to_string(),
+            session: 'F',
+            metadata: None,
+            race_info: parse_race_info(headline),
+            swimmers,
+            content_hash: String::new(),
+            raw_text: None,
+            leadoff: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn results(individual_results: Vec<EventResults>) -> ParsedResults {
+        ParsedResults { individual_results, relay_results: Vec::new(), meet_title: None, errors: Vec::new() }
+    }
+
+    #[test]
+    fn mens_and_womens_events_sharing_a_number_are_diffed_separately() {
+        let old = results(vec![
+            event("Event 3  Women 200 Yard Freestyle", vec![swimmer(1, "Smith, Jane", "Stanford", "1:42.10")]),
+            event("Event 3  Men 200 Yard Freestyle", vec![swimmer(1, "Brown, Sam", "Texas", "1:31.50")]),
+        ]);
+        let new = results(vec![
+            event("Event 3  Women 200 Yard Freestyle", vec![swimmer(1, "Smith, Jane", "Stanford", "1:42.10")]),
+            event("Event 3  Men 200 Yard Freestyle", vec![swimmer(1, "Brown, Sam", "Texas", "1:31.20")]),
+        ]);
+
+        let diff = diff_results(&old, &new);
+
+        assert!(diff.added_events.is_empty());
+        assert!(diff.removed_events.is_empty());
+        assert_eq!(diff.changed_events.len(), 1);
+        let changed = &diff.changed_events[0];
+        assert_eq!(changed.event_name, "Event 3  Men 200 Yard Freestyle");
+        assert!(changed.added.is_empty() && changed.removed.is_empty());
+        assert_eq!(changed.modified, vec![EntryChange {
+            entry: "Brown, Sam (Texas)".to_string(),
+            field: "final_time",
+            old: "1:31.50".to_string(),
+            new: "1:31.20".to_string(),
+        }]);
+    }
+}
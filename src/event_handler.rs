@@ -1,43 +1,292 @@
-use scraper::{Html, Selector};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::error::Error;
+use std::fmt;
 
 use crate::metadata::{EventMetadata, RaceInfo};
-use crate::utils::{is_dq_status, is_year_pattern, is_valid_time_format};
+use crate::time::SwimTime;
+use crate::utils::{has_points_column, hash_content, pre_text, is_dq_status, take_dq_description, is_valid_time_format, parse_place, is_year_pattern, cumulative_split_time, name_first_last, name_last_first};
 
 // ============================================================================
 // DATA STRUCTURES
 // ============================================================================
 
 /// Cumulative split time at a distance
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Split {
     pub distance: u16,
     pub time: String,
+    /// `time` in centiseconds, ignoring record flag letters (None if unparsable)
+    #[serde(default)]
+    pub time_centis: Option<u32>,
 }
 
 /// Individual swimmer result
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Swimmer {
     pub place: Option<u8>,
+    /// Place is shared with another swimmer
+    #[serde(default)]
+    pub tied: bool,
     pub name: String,
     pub year: String,
     pub school: String,
     pub seed_time: Option<String>,
     pub final_time: String,
     pub reaction_time: Option<String>,
-    #[serde(skip)]
+    pub dq_description: Option<String>,
+    /// Qualifying-standard indicators on the final time (e.g. "q", "B"); see
+    /// STANDARD_INDICATORS
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub standards: Vec<String>,
+    /// Para-swimming sport class (e.g. S9, SB8, SM10), at meets that list one
+    #[serde(default)]
+    pub classification: Option<String>,
+    /// Final the swimmer swam in, when the page has final headings
+    #[serde(default)]
+    pub final_type: Option<FinalType>,
     pub splits: Vec<Split>,
+    /// Prelim splits, set only on records built by merge_prelim_final
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prelim_splits: Vec<Split>,
+}
+
+/// Known qualifying-standard indicators and their meanings. Other letters after a time
+/// (e.g. record flags) are left on the time.
+pub const STANDARD_INDICATORS: &[(&str, &str)] = &[
+    ("q", "Qualified for the next round"),
+    ("Q", "Qualified for the next round"),
+    ("A", "A standard"),
+    ("B", "B standard"),
+    ("AA", "AA standard"),
+    ("AAA", "AAA standard"),
+];
+
+/// Returns the meaning of a qualifying-standard indicator, if it is a known one
+pub fn standard_description(indicator: &str) -> Option<&'static str> {
+    STANDARD_INDICATORS.iter()
+        .find(|(code, _)| *code == indicator)
+        .map(|(_, description)| *description)
+}
+
+/// Which final a swimmer swam in, from the section headings of a finals page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FinalType {
+    /// A final
+    Championship,
+    /// B final
+    Consolation,
+    /// C final
+    Bonus,
+    /// Timed final (no prelims)
+    Timed,
+}
+
+impl fmt::Display for FinalType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            FinalType::Championship => "Championship",
+            FinalType::Consolation => "Consolation",
+            FinalType::Bonus => "Bonus",
+            FinalType::Timed => "Timed",
+        };
+        write!(f, "{}", label)
+    }
 }
 
 /// Complete event results with metadata
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EventResults {
     pub event_name: String,
     pub session: char,
     pub metadata: Option<EventMetadata>,
     pub race_info: Option<RaceInfo>,
     pub swimmers: Vec<Swimmer>,
+    /// Hex SHA-256 of the results text, for detecting changed live results
+    #[serde(default)]
+    pub content_hash: String,
+    /// Raw results text the parser saw, kept only with ScrapeOptions::keep_raw
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_text: Option<String>,
+    /// Built from relay leadoff legs rather than parsed from an individual event page
+    /// (see RelayResults::leadoff_event)
+    #[serde(default)]
+    pub leadoff: bool,
+    /// Fallbacks the parser hit (skipped sections, missing fields), empty if it parsed cleanly
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl Split {
+    /// Creates a split, parsing the time into centiseconds
+    pub fn new(distance: u16, time: &str) -> Split {
+        Split {
+            distance,
+            time: time.to_string(),
+            time_centis: time.parse::<SwimTime>().ok().map(|t| t.centiseconds()),
+        }
+    }
+}
+
+impl Swimmer {
+    /// Returns the final time as a SwimTime (None for DQ/NS entries)
+    pub fn final_time_parsed(&self) -> Option<SwimTime> {
+        self.final_time.parse().ok()
+    }
+
+    /// Returns the seed time as a SwimTime (None if missing or NT)
+    pub fn seed_time_parsed(&self) -> Option<SwimTime> {
+        self.seed_time.as_ref()?.parse().ok()
+    }
+
+    /// Returns the name as "First Last" (e.g. "Grant Bochenski")
+    pub fn name_first_last(&self) -> String {
+        name_first_last(&self.name)
+    }
+
+    /// Returns the name as "Last, First" (e.g. "Bochenski, Grant")
+    pub fn name_last_first(&self) -> String {
+        name_last_first(&self.name)
+    }
+}
+
+/// Warning for a swimmer whose split count doesn't fit the event distance
+#[derive(Debug, Clone)]
+pub struct SplitWarning {
+    pub swimmer_name: String,
+    pub expected: Vec<usize>,
+    pub found: usize,
+}
+
+impl fmt::Display for SplitWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let expected = self.expected.iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" or ");
+        write!(f, "{}: found {} splits, expected {}", self.swimmer_name, self.found, expected)
+    }
+}
+
+impl EventResults {
+    /// Returns the entry count stated on the page, for comparing against `swimmers.len()`
+    pub fn entry_count(&self) -> Option<u32> {
+        self.metadata.as_ref()?.entry_count
+    }
+
+    /// Tags each swimmer of a finals page without final headings with the final their
+    /// place falls in (1-8 A final, 9-16 B final). Only call this for events with a
+    /// prelims session; a timed final has no A or B final to infer
+    pub fn infer_final_types(&mut self) {
+        if self.session != 'F' || self.swimmers.iter().any(|s| s.final_type.is_some()) {
+            return;
+        }
+        for swimmer in &mut self.swimmers {
+            swimmer.final_type = swimmer.place.and_then(final_type_from_place);
+        }
+    }
+
+    /// Flags placed swimmers whose split count is implausible for the event distance and course
+    pub fn validate_splits(&self, race_info: &RaceInfo) -> Vec<SplitWarning> {
+        let distance = match race_info.distance {
+            Some(d) if d >= 50 => d as usize,
+            _ => return Vec::new(),
+        };
+
+        // Splits are recorded every 50; long course meets may only record every 100
+        let mut expected = vec![distance / 50];
+        if race_info.course_code() == Some("LCM") && distance >= 200 {
+            expected.push(distance / 100);
+        }
+        // A 50 may list only the final time or a 25 split plus the final time
+        if distance == 50 {
+            expected.push(2);
+        }
+
+        self.swimmers.iter()
+            .filter(|s| s.place.is_some() && !s.splits.is_empty())
+            .filter(|s| !expected.contains(&s.splits.len()))
+            .map(|s| SplitWarning {
+                swimmer_name: s.name.clone(),
+                expected: expected.clone(),
+                found: s.splits.len(),
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// PRELIM/FINAL MERGING
+// ============================================================================
+
+/// Lowercased letters and digits of a value, for loose comparisons
+fn normalize_key(value: &str) -> String {
+    value.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Checks if two swimmer records refer to the same person, tolerating name order
+/// and abbreviated school names
+fn same_swimmer(a: &Swimmer, b: &Swimmer) -> bool {
+    if normalize_key(&a.name_last_first()) != normalize_key(&b.name_last_first()) {
+        return false;
+    }
+    let (school_a, school_b) = (normalize_key(&a.school), normalize_key(&b.school));
+    school_a.contains(&school_b) || school_b.contains(&school_a)
+}
+
+/// Combines prelim and final results into one record per swimmer
+///
+/// Finalists keep their final place, time, and splits, with the prelim time as the
+/// seed time and the prelim splits in `prelim_splits`. Swimmers who only swam prelims
+/// follow without a place or final time.
+pub fn merge_prelim_final(prelim: &EventResults, final_: &EventResults) -> EventResults {
+    let mut matched = vec![false; prelim.swimmers.len()];
+
+    let mut swimmers: Vec<Swimmer> = final_.swimmers.iter()
+        .map(|swimmer| {
+            let mut merged = swimmer.clone();
+            let prelim_idx = (0..prelim.swimmers.len())
+                .find(|&i| !matched[i] && same_swimmer(&prelim.swimmers[i], swimmer));
+            if let Some(i) = prelim_idx {
+                matched[i] = true;
+                merged.seed_time = Some(prelim.swimmers[i].final_time.clone());
+                merged.prelim_splits = prelim.swimmers[i].splits.clone();
+            }
+            merged
+        })
+        .collect();
+
+    swimmers.extend(
+        prelim.swimmers.iter()
+            .zip(&matched)
+            .filter(|(_, was_matched)| !**was_matched)
+            .map(|(swimmer, _)| Swimmer {
+                place: None,
+                tied: false,
+                seed_time: Some(swimmer.final_time.clone()),
+                final_time: String::new(),
+                reaction_time: None,
+                dq_description: None,
+                final_type: None,
+                splits: Vec::new(),
+                prelim_splits: swimmer.splits.clone(),
+                ..swimmer.clone()
+            }),
+    );
+
+    EventResults {
+        event_name: final_.event_name.clone(),
+        session: final_.session,
+        metadata: final_.metadata.clone(),
+        race_info: final_.race_info.clone(),
+        swimmers,
+        content_hash: final_.content_hash.clone(),
+        raw_text: final_.raw_text.clone(),
+        leadoff: final_.leadoff,
+        warnings: prelim.warnings.iter().chain(&final_.warnings).cloned().collect(),
+    }
 }
 
 // ============================================================================
@@ -52,31 +301,61 @@ pub fn parse_individual_event_html(
     metadata: Option<EventMetadata>,
     race_info: Option<RaceInfo>,
 ) -> Result<EventResults, Box<dyn Error>> {
-    let document = Html::parse_document(html);
+    parse_individual_event_pages(html, &[], event_name, session, metadata, race_info)
+}
+
+/// Parses an individual event whose results continue on further pages. Each continuation
+/// page's header is dropped and its results appended, so a swimmer's splits carry over
+/// the page break.
+pub fn parse_individual_event_pages(
+    html: &str,
+    continuations: &[String],
+    event_name: &str,
+    session: char,
+    metadata: Option<EventMetadata>,
+    race_info: Option<RaceInfo>,
+) -> Result<EventResults, Box<dyn Error>> {
     let mut swimmers = Vec::new();
+    let mut content_hash = String::new();
+    let mut warnings = Vec::new();
 
-    let pre_selector = Selector::parse("pre").unwrap();
-    if let Some(pre) = document.select(&pre_selector).next() {
-        let content = pre.text().collect::<String>();
+    if let Some(content) = event_text(html, continuations) {
+        content_hash = hash_content(&content);
         let lines: Vec<&str> = content.lines().collect();
 
+        let mut final_type: Option<FinalType> = None;
         let mut i = 0;
         while i < lines.len() {
             let current_line = lines[i].trim();
 
+            if let Some(heading) = parse_final_heading(current_line) {
+                final_type = Some(heading);
+                i += 1;
+                continue;
+            }
+
             if is_swimmer_line(current_line) {
-                // Find the next swimmer line or end of content
+                // Find the next swimmer line, final heading, or end of content
                 let mut next_idx = i + 1;
                 while next_idx < lines.len() {
                     let next_line = lines[next_idx].trim();
-                    if !next_line.is_empty() && is_swimmer_line(next_line) {
+                    if !next_line.is_empty()
+                        && (is_swimmer_line(next_line) || parse_final_heading(next_line).is_some())
+                    {
                         break;
                     }
                     next_idx += 1;
                 }
 
-                if let Some(swimmer) = parse_swimmer_section(&lines[i..next_idx]) {
-                    swimmers.push(swimmer);
+                match parse_swimmer_section(&lines[i..next_idx]) {
+                    Some(mut swimmer) => {
+                        if swimmer.year.is_empty() {
+                            warnings.push(format!("missing year: {}", swimmer.name));
+                        }
+                        swimmer.final_type = final_type;
+                        swimmers.push(swimmer);
+                    }
+                    None => warnings.push(format!("swimmer section skipped: too few fields: {}", current_line)),
                 }
 
                 i = next_idx;
@@ -84,6 +363,14 @@ pub fn parse_individual_event_html(
             }
             i += 1;
         }
+
+        if swimmers.is_empty() {
+            warnings.push("no swimmers found".to_string());
+        } else if swimmers.iter().all(|s| s.splits.is_empty()) {
+            warnings.push("no splits found".to_string());
+        }
+    } else {
+        warnings.push("no results text found".to_string());
     }
 
     Ok(EventResults {
@@ -92,6 +379,10 @@ pub fn parse_individual_event_html(
         metadata,
         race_info,
         swimmers,
+        content_hash,
+        raw_text: None,
+        leadoff: false,
+        warnings,
     })
 }
 
@@ -99,38 +390,126 @@ pub fn parse_individual_event_html(
 // SWIMMER PARSING
 // ============================================================================
 
-/// Checks if a line starts a swimmer result (place number or -- for DQ)
+/// Recognizes final section headings such as "=== A - Final ===", "Consolation Final",
+/// or "Timed Finals"
+fn parse_final_heading(line: &str) -> Option<FinalType> {
+    let heading = line.trim_matches(|c: char| c == '=' || c.is_whitespace()).to_lowercase();
+    let words: Vec<&str> = heading.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    match words.as_slice() {
+        ["a", "final" | "finals"] | ["championship", "final" | "finals"] => Some(FinalType::Championship),
+        ["b", "final" | "finals"] | ["consolation", "final" | "finals"] => Some(FinalType::Consolation),
+        ["c", "final" | "finals"] | ["bonus", "final" | "finals"] => Some(FinalType::Bonus),
+        ["timed", "final" | "finals"] => Some(FinalType::Timed),
+        _ => None,
+    }
+}
+
+/// Infers the final from a finals place: 1-8 swam the A final, 9-16 the B final
+fn final_type_from_place(place: u8) -> Option<FinalType> {
+    match place {
+        1..=8 => Some(FinalType::Championship),
+        9..=16 => Some(FinalType::Consolation),
+        _ => None,
+    }
+}
+
+/// Checks if a line starts a swimmer result: a place number (or -- for DQ) followed later
+/// by a time or status, so dividers and progress lines like "16 of 24" are rejected
 fn is_swimmer_line(line: &str) -> bool {
-    match line.split_whitespace().next() {
-        Some(token) => {
-            let is_place = token.chars().all(|c| c.is_ascii_digit());
-            let is_dq = token == "--";
-            is_place || is_dq
-        }
+    let mut tokens = line.split_whitespace();
+    let starts_result = match tokens.next() {
+        Some(token) => parse_place(token).is_some() || token == "--",
         None => false,
+    };
+    starts_result && tokens.any(is_result_token)
+}
+
+/// Checks if a token is a time (allowing an exhibition "x" prefix and standard suffixes)
+/// or a status such as DQ, NS, or NT
+fn is_result_token(token: &str) -> bool {
+    let time = token.trim_start_matches(['x', 'X']).trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    is_valid_time_format(time) || is_dq_status(token) || matches!(token, "NS" | "SCR" | "NT")
+}
+
+/// Returns an event's results text: the first page's pre block followed by the body of
+/// each continuation page. Its hash is the event's content_hash.
+pub(crate) fn event_text(html: &str, continuations: &[String]) -> Option<String> {
+    let mut content = pre_text(html)?;
+    for page in continuations {
+        if let Some(text) = pre_text(page) {
+            content.push('\n');
+            content.push_str(&continuation_body(&text));
+        }
     }
+    Some(content)
+}
+
+/// Drops a continuation page's repeated header (meet name, headline, records, column
+/// titles), keeping everything from the first result, split, or final heading line on
+fn continuation_body(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.iter()
+        .position(|line| {
+            let line = line.trim();
+            let first = line.split_whitespace().next().unwrap_or("");
+            is_swimmer_line(line)
+                || parse_final_heading(line).is_some()
+                || first.starts_with("r:")
+                || first.starts_with("r+")
+                || cumulative_split_time(first).is_some()
+        })
+        .unwrap_or(lines.len());
+    lines[start..].join("\n")
+}
+
+/// Checks if a line continues a swimmer's DQ description rather than starting the splits
+fn is_dq_description_line(line: &str) -> bool {
+    !line.is_empty()
+        && !line.starts_with("r:")
+        && !line.starts_with("r+")
+        && line.chars().any(|c| c.is_ascii_alphabetic())
+        && !line.split_whitespace().any(|p| cumulative_split_time(p).is_some())
+        && parse_final_heading(line).is_none()
 }
 
 /// Parses a swimmer section (main line + split lines) into a Swimmer
 fn parse_swimmer_section(lines: &[&str]) -> Option<Swimmer> {
     let main_line = lines[0].trim();
-    let parts: Vec<&str> = main_line.split_whitespace().collect();
+    let mut parts: Vec<&str> = main_line.split_whitespace().collect();
+
+    // Para meets add a classification column; remove it so the positional logic below holds
+    let classification = parts.iter()
+        .skip(1)
+        .position(|p| is_classification(p))
+        .map(|i| parts.remove(i + 1).to_string());
+
+    // A standard may follow the final time as its own token ("58.12 B", or before points)
+    let mut standards: Vec<String> = Vec::new();
+    let standalone = (parts.len().saturating_sub(2)..parts.len())
+        .find(|&i| i > 1 && standard_description(parts[i]).is_some() && is_valid_time_format(parts[i - 1]));
+    if let Some(i) = standalone {
+        standards.push(parts.remove(i).to_string());
+    }
 
     if parts.len() < 5 {
         return None;
     }
 
     let is_dq_entry = parts[0] == "--";
-    let place: Option<u8> = if is_dq_entry {
-        None
+    let (place, tied) = if is_dq_entry {
+        (None, false)
     } else {
-        Some(parts[0].parse().ok()?)
+        let (place, tied) = parse_place(parts[0])?;
+        (Some(place), tied)
     };
 
     let last = parts.last()?;
 
     // Determine field positions based on entry type
-    let (final_time, seed_time, end_offset) = if last.parse::<u8>().is_ok() {
+    let (final_time, seed_time, end_offset) = if has_points_column(&parts) {
         (parts[parts.len() - 2], Some(parts[parts.len() - 3].to_string()), 3)
     } else if is_dq_status(last) {
         (*last, Some(parts[parts.len() - 2].to_string()), 2)
@@ -143,6 +522,15 @@ fn parse_swimmer_section(lines: &[&str]) -> Option<Swimmer> {
         (*last, seed, 2)
     };
 
+    // Or be attached to it ("58.12q")
+    let final_time = match split_standard_suffix(final_time) {
+        Some((time, indicator)) => {
+            standards.insert(0, indicator.to_string());
+            time
+        }
+        None => final_time,
+    };
+
     // Find year position
     let mut year_idx = None;
     for (i, &part) in parts.iter().enumerate().skip(1).take(parts.len().saturating_sub(end_offset + 1)) {
@@ -151,33 +539,93 @@ fn parse_swimmer_section(lines: &[&str]) -> Option<Swimmer> {
             break;
         }
     }
-    let year_idx = year_idx?;
-
-    let name = parts[1..year_idx].join(" ");
-    let year = parts[year_idx];
     let school_end = parts.len() - end_offset;
-    let school = parts[year_idx + 1..school_end].join(" ");
 
-    let (reaction_time, splits) = parse_splits(lines);
+    let (name, year, school) = match year_idx {
+        Some(yi) => (
+            parts[1..yi].join(" "),
+            parts[yi].to_string(),
+            parts[yi + 1..school_end].join(" "),
+        ),
+        None => {
+            // No year column: the column gap after the name bounds it, or on single-spaced
+            // lines "Last, First" (one first-name word); the trailing times bound the school
+            let name_end = name_column_width(main_line)
+                .filter(|words| 1 + words < school_end)
+                .or_else(|| parts[1..school_end].iter().position(|p| p.ends_with(',')).map(|i| i + 2))
+                .map(|words| (1 + words).min(school_end))
+                .unwrap_or(school_end);
+            (
+                parts[1..name_end].join(" "),
+                String::new(),
+                parts[name_end..school_end].join(" "),
+            )
+        }
+    };
+
+    // DQ descriptions follow the swimmer line and may wrap onto further lines
+    let (dq_description, description_lines) = if is_dq_entry {
+        take_dq_description(&lines[1..], is_dq_description_line)
+    } else {
+        (None, 0)
+    };
+
+    let split_start_idx = 1 + description_lines;
+    let (reaction_time, splits) = parse_splits(&lines[split_start_idx..]);
 
     Some(Swimmer {
         place,
+        tied,
         name,
-        year: year.to_string(),
+        year,
         school,
         seed_time,
         final_time: final_time.to_string(),
         reaction_time,
+        dq_description,
+        standards,
+        classification,
+        final_type: None,
         splits,
+        prelim_splits: Vec::new(),
     })
 }
 
-/// Extracts reaction time and split times from swimmer lines
+/// Splits a known standard indicator off the end of a time ("58.12q" -> ("58.12", "q"))
+fn split_standard_suffix(time: &str) -> Option<(&str, &str)> {
+    let letters = time.len() - time.trim_end_matches(|c: char| c.is_ascii_alphabetic()).len();
+    let (value, indicator) = time.split_at(time.len() - letters);
+    if letters == 0 || !is_valid_time_format(value) {
+        return None;
+    }
+    standard_description(indicator).map(|_| (value, indicator))
+}
+
+/// Checks for a para-swimming sport class: S, SB, or SM followed by 1-14
+fn is_classification(token: &str) -> bool {
+    let digits = token.strip_prefix("SB")
+        .or_else(|| token.strip_prefix("SM"))
+        .or_else(|| token.strip_prefix('S'))
+        .unwrap_or("");
+    !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && digits.parse::<u8>().map(|n| (1..=14).contains(&n)).unwrap_or(false)
+}
+
+/// Counts the words of the name column on a fixed-width swimmer line: the text after
+/// the place up to the first gap of two or more spaces ("1 Smith, Mary Kate  Stanford ..." -> 3)
+fn name_column_width(line: &str) -> Option<usize> {
+    let after_place = line.trim_start().split_once(char::is_whitespace)?.1.trim_start();
+    let (name, _) = after_place.split_once("  ")?;
+    Some(name.split_whitespace().count())
+}
+
+/// Extracts reaction time and split times from the lines below a swimmer's main line
 fn parse_splits(lines: &[&str]) -> (Option<String>, Vec<Split>) {
     let mut splits = Vec::new();
     let mut reaction_time: Option<String> = None;
 
-    for line in lines.iter().skip(1) {
+    for line in lines {
         let line = line.trim();
         if line.is_empty() {
             continue;
@@ -193,18 +641,337 @@ fn parse_splits(lines: &[&str]) -> (Option<String>, Vec<Split>) {
                 continue;
             }
 
-            let is_time = !part.contains('(')
-                && part.chars().next().is_some_and(|c| c.is_ascii_digit())
-                && is_valid_time_format(part);
-
-            if is_time {
-                splits.push(Split {
-                    distance: (splits.len() as u16 + 1) * 50,
-                    time: part.to_string(),
-                });
+            if let Some(time) = cumulative_split_time(part) {
+                splits.push(Split::new((splits.len() as u16 + 1) * 50, time));
             }
         }
     }
 
     (reaction_time, splits)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(body: &str) -> String {
+        format!("<html><body><pre>{}</pre></body></html>", body)
+    }
+
+    #[test]
+    fn final_headings_tag_each_swimmer() {
+        let html = page("\
+=== A - Final ===
+  1 Smith, Jane          SR Stanford          1:58.00    1:56.10
+     r:+0.65  27.10        56.20 (29.10)
+  2 Jones, Amy           JR Texas             1:58.50    1:57.00
+     r:+0.70  27.30        56.40 (29.10)
+=== B - Final ===
+  9 Brown, Kate          SO Cal               1:59.00    1:58.20
+     r:+0.66  27.80        57.10 (29.30)
+ -- Green, Lia           FR Virginia          1:59.10         DQ
+     Early take-off swimmer #2
+Bonus Final
+ 17 White, Ana           FR Florida           2:00.00    1:59.90
+     r:+0.71  28.00        57.80 (29.80)
+");
+        let results = parse_individual_event_html(&html, "Women 200 Yard Freestyle", 'F', None, None).unwrap();
+        let types: Vec<Option<FinalType>> = results.swimmers.iter().map(|s| s.final_type).collect();
+        assert_eq!(types, vec![
+            Some(FinalType::Championship),
+            Some(FinalType::Championship),
+            Some(FinalType::Consolation),
+            Some(FinalType::Consolation),
+            Some(FinalType::Bonus),
+        ]);
+        assert_eq!(results.swimmers[3].dq_description.as_deref(), Some("Early take-off swimmer #2"));
+    }
+
+    #[test]
+    fn final_type_falls_back_to_place_range() {
+        let html = page("\
+  8 Smith, Jane          SR Stanford          1:58.00    1:56.10
+  9 Brown, Kate          SO Cal               1:59.00    1:58.20
+ 17 White, Ana           FR Florida           2:00.00    1:59.90
+");
+        let mut finals = parse_individual_event_html(&html, "Women 200 Yard Freestyle", 'F', None, None).unwrap();
+        assert!(finals.swimmers.iter().all(|s| s.final_type.is_none()));
+        finals.infer_final_types();
+        let types: Vec<Option<FinalType>> = finals.swimmers.iter().map(|s| s.final_type).collect();
+        assert_eq!(types, vec![Some(FinalType::Championship), Some(FinalType::Consolation), None]);
+
+        let mut prelims = parse_individual_event_html(&html, "Women 200 Yard Freestyle", 'P', None, None).unwrap();
+        prelims.infer_final_types();
+        assert!(prelims.swimmers.iter().all(|s| s.final_type.is_none()));
+    }
+
+    #[test]
+    fn timed_final_keeps_final_type_unset() {
+        let html = page("\
+  1 Smith, Jane          SR Stanford          9:40.00    9:35.10
+  9 Brown, Kate          SO Cal               9:59.00    9:58.20
+");
+        // A timed final has no prelims, so the meet runner never infers from place
+        let results = parse_individual_event_html(&html, "Women 1000 Yard Freestyle", 'F', None, None).unwrap();
+        assert!(results.swimmers.iter().all(|s| s.final_type.is_none()));
+    }
+
+    #[test]
+    fn two_line_dq_reason_is_joined_before_the_splits() {
+        let html = page("\
+  1 Smith, Jane          SR Stanford          1:58.00    1:56.10
+     r:+0.65  27.10        56.20 (29.10)
+ -- Green, Lia           FR Virginia          1:59.10         DQ
+     Early take-off swimmer #3
+     Stroke infraction - butterfly kick
+     r:+0.66  27.80        57.10 (29.30)
+  2 Jones, Amy           JR Texas             1:58.50    1:57.00
+");
+        let results = parse_individual_event_html(&html, "Women 200 Yard Freestyle", 'F', None, None).unwrap();
+        let dq = &results.swimmers[1];
+        assert_eq!((dq.name.as_str(), dq.place, dq.final_time.as_str()), ("Green, Lia", None, "DQ"));
+        assert_eq!(
+            dq.dq_description.as_deref(),
+            Some("Early take-off swimmer #3 Stroke infraction - butterfly kick"),
+        );
+        assert_eq!(dq.reaction_time.as_deref(), Some("r:+0.66"));
+        assert_eq!(dq.splits.len(), 2);
+        assert_eq!(results.swimmers[2].name, "Jones, Amy");
+    }
+
+    #[test]
+    fn false_start_dq_keeps_its_reason() {
+        let html = page("\
+  1 Smith, Jane          SR Stanford          1:58.00    1:56.10
+     r:+0.65  27.10        56.20 (29.10)
+ -- Brown, Kate          SO Cal               1:59.00         DQ
+     False start
+  2 Jones, Amy           JR Texas             1:58.50    1:57.00
+     r:+0.70  27.30        56.40 (29.10)
+");
+        let results = parse_individual_event_html(&html, "Women 200 Yard Freestyle", 'F', None, None).unwrap();
+        let names: Vec<&str> = results.swimmers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Smith, Jane", "Brown, Kate", "Jones, Amy"]);
+
+        let dq = &results.swimmers[1];
+        assert_eq!((dq.place, dq.year.as_str(), dq.school.as_str()), (None, "SO", "Cal"));
+        assert_eq!(dq.seed_time.as_deref(), Some("1:59.00"));
+        assert_eq!(dq.final_time, "DQ");
+        assert_eq!(dq.dq_description.as_deref(), Some("False start"));
+        assert!(dq.splits.is_empty());
+    }
+
+    #[test]
+    fn divider_and_progress_lines_are_not_swimmers() {
+        for line in ["A - Final", "=== A - Final ===", "Swim-off", "16 of 24", "  16 of 24 entries"] {
+            assert!(!is_swimmer_line(line), "{}", line);
+        }
+        assert!(is_swimmer_line("  16 Brown, Kate          SO Cal               1:59.00    1:58.20"));
+    }
+
+    #[test]
+    fn qualifier_attached_or_spaced_becomes_a_standard() {
+        for line in [
+            "  1 Smith, Jane          SR Stanford            58.50      58.12q",
+            "  1 Smith, Jane          SR Stanford            58.50      58.12 q",
+        ] {
+            let swimmer = parse_swimmer_section(&[line]).unwrap();
+            assert_eq!(swimmer.final_time, "58.12", "{}", line);
+            assert_eq!(swimmer.standards, vec!["q"], "{}", line);
+            assert_eq!(swimmer.seed_time.as_deref(), Some("58.50"), "{}", line);
+            assert_eq!(swimmer.school, "Stanford", "{}", line);
+        }
+    }
+
+    #[test]
+    fn para_classification_is_read_from_its_column() {
+        let swimmer = parse_swimmer_section(&["1 Smith, Jane  S9  SR Stanford 1:05.00 1:04.10"]).unwrap();
+        assert_eq!(swimmer.classification.as_deref(), Some("S9"));
+        assert_eq!(swimmer.name, "Smith, Jane");
+        assert_eq!(swimmer.year, "SR");
+        assert_eq!(swimmer.school, "Stanford");
+        assert_eq!((swimmer.seed_time.as_deref(), swimmer.final_time.as_str()), (Some("1:05.00"), "1:04.10"));
+
+        let swimmer = parse_swimmer_section(&["2 Jones, Amy  SB8  19 Texas 1:16.00 1:15.20"]).unwrap();
+        assert_eq!(swimmer.classification.as_deref(), Some("SB8"));
+        assert_eq!((swimmer.name.as_str(), swimmer.year.as_str(), swimmer.school.as_str()), ("Jones, Amy", "19", "Texas"));
+    }
+
+    #[test]
+    fn school_ending_in_a_number_with_and_without_points() {
+        let swimmer = parse_swimmer_section(&["  1 Smith, Jane          15 Club Wolverine 2     1:05.00    1:04.10"]).unwrap();
+        assert_eq!((swimmer.school.as_str(), swimmer.seed_time.as_deref(), swimmer.final_time.as_str()),
+            ("Club Wolverine 2", Some("1:05.00"), "1:04.10"));
+
+        let swimmer = parse_swimmer_section(&["  1 Smith, Jane          15 Club Wolverine 2     1:05.00    1:04.10   20"]).unwrap();
+        assert_eq!((swimmer.school.as_str(), swimmer.seed_time.as_deref(), swimmer.final_time.as_str()),
+            ("Club Wolverine 2", Some("1:05.00"), "1:04.10"));
+
+        let swimmer = parse_swimmer_section(&["  2 Jones, Amy           16 Unattached 1         1:06.00    1:05.30"]).unwrap();
+        assert_eq!((swimmer.school.as_str(), swimmer.final_time.as_str()), ("Unattached 1", "1:05.30"));
+    }
+
+    #[test]
+    fn splits_continue_across_page_boundary() {
+        let first = page("\
+Event 3  Women 500 Yard Freestyle
+===============================================================================
+  1 Smith, Jane          SR Stanford          4:40.00    4:35.10
+     r:+0.65  26.10        54.20 (28.10)
+");
+        let continuation = page("\
+Event 3  Women 500 Yard Freestyle
+    Name                    Yr School                 Seed Time  Finals Time
+     1:22.50 (28.30)    1:50.90 (28.40)
+  2 Jones, Amy           JR Texas             4:41.00    4:37.00
+     r:+0.70  26.30        54.40 (28.10)
+");
+        let continuations = vec![continuation];
+        let results = parse_individual_event_pages(&first, &continuations, "Women 500 Yard Freestyle", 'F', None, None).unwrap();
+
+        assert_eq!(results.swimmers.len(), 2);
+        let distances: Vec<u16> = results.swimmers[0].splits.iter().map(|s| s.distance).collect();
+        assert_eq!(distances, vec![50, 100, 150, 200]);
+        assert_eq!(results.swimmers[1].splits.len(), 2);
+        assert_eq!(results.content_hash, hash_content(&event_text(&first, &continuations).unwrap()));
+    }
+
+    #[test]
+    fn tied_swimmers_keep_their_place() {
+        let html = page("\
+  1 Smith, Jane          SR Stanford          1:58.00    1:56.10
+  2 Jones, Amy           JR Texas             1:58.50    1:57.00
+ 3T Brown, Kate          SO Cal               1:59.00    1:57.20
+ *3 White, Ana           FR Florida           2:00.00    1:57.20
+  5 Green, Lia           FR Virginia          1:59.10    1:58.00
+");
+        let results = parse_individual_event_html(&html, "Women 200 Yard Freestyle", 'F', None, None).unwrap();
+        let places: Vec<(Option<u8>, bool)> = results.swimmers.iter().map(|s| (s.place, s.tied)).collect();
+        assert_eq!(places, vec![
+            (Some(1), false),
+            (Some(2), false),
+            (Some(3), true),
+            (Some(3), true),
+            (Some(5), false),
+        ]);
+        assert_eq!(results.swimmers[2].name, "Brown, Kate");
+        assert_eq!(results.swimmers[3].name, "White, Ana");
+    }
+
+    /// xorshift64 generator, so the randomized tests below need no extra dependency
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+
+        fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+            items[self.below(items.len())]
+        }
+    }
+
+    /// Tokens seen on swimmer and split lines, plus malformed ones; none is a split time
+    const NOISE: &[&str] = &[
+        "--", "1", "12", "*3", "Smith,", "Jane", "Mary", "SR", "FR", "5J", "Stanford", "Cal", "U-14",
+        "S9", "SB8", "DQ", "NS", "DFS", "DNF", "SCR", "q", "Q", "B", "AA", "16", "18.5", "x1:02.30",
+        "r:+0.65", "r:-0.02", "r:", "(29.10)", "(1:02.30)", "(", ")", "1:2.34", "1:60.00", "58.1", "58.123",
+        ":", ".", "..", "1::02.30", "-", "#2", "Early", "take-off", "swimmer", "=",
+    ];
+
+    /// Tokens read as split times
+    const TIMES: &[&str] = &["26.10", "54.20", "1:22.50", "9:58.04", "14:35.12", "58.12q", "27.80(29.30)"];
+
+    /// Random swimmer section for a race of `distance`: a swimmer line, then split lines
+    /// holding at most one time per 50 mixed with noise tokens
+    fn random_section(rng: &mut Rng, distance: u16) -> Vec<String> {
+        let mut main: Vec<&str> = (0..rng.below(12)).map(|_| rng.pick(NOISE)).collect();
+        for _ in 0..rng.below(3) {
+            let at = rng.below(main.len() + 1);
+            main.insert(at, rng.pick(TIMES));
+        }
+        let mut lines = vec![main.join(" ")];
+
+        let mut times_left = rng.below(distance as usize / 50 + 1);
+        for _ in 0..rng.below(6) {
+            let mut line: Vec<&str> = Vec::new();
+            for _ in 0..rng.below(10) {
+                if times_left > 0 && rng.below(2) == 0 {
+                    line.push(rng.pick(TIMES));
+                    times_left -= 1;
+                } else {
+                    line.push(rng.pick(NOISE));
+                }
+            }
+            lines.push(format!("     {}", line.join(" ")));
+        }
+        lines
+    }
+
+    #[test]
+    fn random_token_streams_never_panic_or_overcount_splits() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..5000 {
+            let distance = [50, 100, 200, 400, 500, 1000, 1650][rng.below(7)];
+            let section = random_section(&mut rng, distance);
+            let lines: Vec<&str> = section.iter().map(String::as_str).collect();
+
+            if let Some(swimmer) = parse_swimmer_section(&lines) {
+                assert!(
+                    swimmer.splits.len() <= distance as usize / 50,
+                    "{} splits for a {}: {:?}", swimmer.splits.len(), distance, lines,
+                );
+                for (i, split) in swimmer.splits.iter().enumerate() {
+                    assert_eq!(split.distance as usize, (i + 1) * 50);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn swimmer_without_year_keeps_multi_word_first_name() {
+        let swimmer = parse_swimmer_section(&["  1 Smith, Mary Kate  Stanford 1:05.00 1:04.10"]).unwrap();
+        assert_eq!(swimmer.name, "Smith, Mary Kate");
+        assert_eq!(swimmer.year, "");
+        assert_eq!(swimmer.school, "Stanford");
+        assert_eq!(swimmer.final_time, "1:04.10");
+
+        let swimmer = parse_swimmer_section(&["  2 Lee, Anna Mae          Cal Berkeley         1:06.00    1:05.20"]).unwrap();
+        assert_eq!((swimmer.name.as_str(), swimmer.school.as_str()), ("Lee, Anna Mae", "Cal Berkeley"));
+
+        // Single-spaced lines have no column gap, so only one first-name word is assumed
+        let swimmer = parse_swimmer_section(&["3 Jones, Amy Texas 1:07.00 1:06.30"]).unwrap();
+        assert_eq!((swimmer.name.as_str(), swimmer.school.as_str()), ("Jones, Amy", "Texas"));
+    }
+
+    #[test]
+    fn mile_splits_past_ten_minutes_keep_counting() {
+        // 1650 at 27.50 per 50 after a 26.00 opener, six splits to a line as HY-TEK prints them
+        let centis: Vec<u32> = (0..33).map(|i| 2600 + i * 2750).collect();
+        let format = |c: u32| match c / 6000 {
+            0 => format!("{}.{:02}", c / 100, c % 100),
+            minutes => format!("{}:{:02}.{:02}", minutes, c / 100 % 60, c % 100),
+        };
+        let lines: Vec<String> = centis.chunks(6)
+            .map(|chunk| chunk.iter().map(|&c| format!("{} (27.50)", format(c))).collect::<Vec<_>>().join("  "))
+            .map(|line| format!("     {}", line))
+            .collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+
+        let (_, splits) = parse_splits(&lines);
+        assert_eq!(splits.len(), 33);
+        assert_eq!(splits.last().map(|s| s.distance), Some(1650));
+        assert_eq!(splits[20].time, "9:36.00");
+        assert_eq!(splits[21].time, "10:03.50");
+        assert_eq!(splits[32].time, "15:06.00");
+        let read: Vec<Option<u32>> = splits.iter().map(|s| s.time_centis).collect();
+        assert_eq!(read, centis.into_iter().map(Some).collect::<Vec<_>>());
+    }
+}
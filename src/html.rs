@@ -0,0 +1,225 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::output::{output_swimmers, output_teams, sorted_events, truncate_school, OutputOptions};
+use crate::metadata::EventMetadata;
+use crate::time::SwimTime;
+use crate::utils::session_label;
+use crate::{EventRef, ParsedResults};
+
+/// Inline styles kept small so the report stays one readable file
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.6em; text-align: left; }
+th { background: #eee; cursor: pointer; }
+td.num { text-align: right; }
+";
+
+/// Sorts a table by the clicked column, using data-sort values when present
+const SORT_SCRIPT: &str = "
+document.querySelectorAll('th').forEach(function (th) {
+  th.addEventListener('click', function () {
+    var table = th.closest('table');
+    var body = table.tBodies[0];
+    var col = th.cellIndex;
+    var asc = th.dataset.dir !== 'asc';
+    th.dataset.dir = asc ? 'asc' : 'desc';
+    var key = function (row) {
+      var cell = row.cells[col];
+      var value = cell.dataset.sort !== undefined ? cell.dataset.sort : cell.textContent;
+      var number = parseFloat(value);
+      return isNaN(number) ? value.toLowerCase() : number;
+    };
+    Array.from(body.rows)
+      .sort(function (a, b) {
+        var x = key(a), y = key(b);
+        return (x < y ? -1 : x > y ? 1 : 0) * (asc ? 1 : -1);
+      })
+      .forEach(function (row) { body.appendChild(row); });
+  });
+});
+";
+
+// ============================================================================
+// HTML OUTPUT
+// ============================================================================
+
+/// Escapes text for use in HTML content and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Sort value for a place cell; DQ/NS entries sort after every place
+fn place_cell(place: Option<u8>) -> String {
+    match place {
+        Some(place) => format!("<td class=\"num\" data-sort=\"{}\">{}</td>", place, place),
+        None => "<td class=\"num\" data-sort=\"999\">--</td>".to_string(),
+    }
+}
+
+/// Sort value for a time cell, in centiseconds; unparsable times (DQ, NS) sort last
+fn time_cell(time: &str) -> String {
+    let sort = time.parse::<SwimTime>().map(|t| t.centiseconds()).unwrap_or(u32::MAX);
+    format!("<td class=\"num\" data-sort=\"{}\">{}</td>", sort, escape(time))
+}
+
+/// Writes a single static HTML report with a table of contents, the meet metadata, and a
+/// sortable table per event, honoring top_n and the metadata toggle
+pub fn write_html_report(
+    results: &ParsedResults,
+    options: &OutputOptions,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_html_report_to(results, options, &mut writer)?;
+    log::info!("Report written to {}", path.display());
+    Ok(())
+}
+
+/// Writes the HTML report to any writer
+pub fn write_html_report_to<W: Write>(
+    results: &ParsedResults,
+    options: &OutputOptions,
+    writer: &mut W,
+) -> Result<(), Box<dyn Error>> {
+    let title = escape(results.meet_title.as_deref().unwrap_or("Results"));
+    let events = sorted_events(results);
+
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, "<html>\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(writer, "<title>{}</title>\n<style>{}</style>\n</head>\n<body>", title, STYLE)?;
+    writeln!(writer, "<h1>{}</h1>", title)?;
+
+    let metadata = events.iter().find_map(|event| match event {
+        EventRef::Individual(r) => r.metadata.as_ref(),
+        EventRef::Relay(r) => r.metadata.as_ref(),
+    });
+    if let (true, Some(metadata)) = (options.metadata, metadata) {
+        write_meet_metadata(writer, metadata)?;
+    }
+
+    writeln!(writer, "<h2>Events</h2>\n<ul>")?;
+    for (i, event) in events.iter().enumerate() {
+        let (name, session) = event_heading(event);
+        writeln!(writer, "<li><a href=\"#event-{}\">{} ({})</a></li>", i, escape(name), session)?;
+    }
+    writeln!(writer, "</ul>")?;
+
+    for (i, event) in events.iter().enumerate() {
+        let (name, session) = event_heading(event);
+        writeln!(writer, "<h2 id=\"event-{}\">{} ({})</h2>", i, escape(name), session)?;
+
+        match event {
+            EventRef::Individual(event) => {
+                if options.metadata {
+                    write_records(writer, event.metadata.as_ref())?;
+                }
+                writeln!(writer, "<table>\n<thead><tr><th>Place</th><th>Name</th><th>Year</th><th>School</th><th>Seed</th><th>Time</th></tr></thead>\n<tbody>")?;
+                for swimmer in output_swimmers(event, options) {
+                    writeln!(
+                        writer,
+                        "<tr>{}<td>{}</td><td>{}</td><td>{}</td>{}{}</tr>",
+                        place_cell(swimmer.place),
+                        escape(&options.name_format.apply(&swimmer.name)),
+                        escape(&swimmer.year),
+                        escape(&truncate_school(&swimmer.school, options)),
+                        time_cell(swimmer.seed_time.as_deref().unwrap_or("")),
+                        time_cell(&swimmer.final_time),
+                    )?;
+                }
+            }
+            EventRef::Relay(event) => {
+                if options.metadata {
+                    write_records(writer, event.metadata.as_ref())?;
+                }
+                writeln!(writer, "<table>\n<thead><tr><th>Place</th><th>Team</th><th>Swimmers</th><th>Seed</th><th>Time</th></tr></thead>\n<tbody>")?;
+                for team in output_teams(event, options) {
+                    let swimmers: Vec<String> = team.legs().iter()
+                        .filter(|s| !s.name.is_empty())
+                        .map(|s| options.name_format.apply(&s.name))
+                        .collect();
+                    writeln!(
+                        writer,
+                        "<tr>{}<td>{}</td><td>{}</td>{}{}</tr>",
+                        place_cell(team.place),
+                        escape(&truncate_school(&team.team_name, options)),
+                        escape(&swimmers.join(", ")),
+                        time_cell(team.seed_time.as_deref().unwrap_or("")),
+                        time_cell(&team.final_time),
+                    )?;
+                }
+            }
+        }
+        writeln!(writer, "</tbody>\n</table>")?;
+    }
+
+    writeln!(writer, "<script>{}</script>\n</body>\n</html>", SORT_SCRIPT)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Returns an event's headline and session label
+fn event_heading<'a>(event: &EventRef<'a>) -> (&'a str, &'static str) {
+    match *event {
+        EventRef::Individual(r) => (&r.event_name, session_label(r.session)),
+        EventRef::Relay(r) => (&r.event_name, session_label(r.session)),
+    }
+}
+
+/// Writes the meet name and venue shared by the meet's events
+fn write_meet_metadata<W: Write>(writer: &mut W, metadata: &EventMetadata) -> Result<(), Box<dyn Error>> {
+    if let Some(ref meet_name) = metadata.meet_name {
+        writeln!(writer, "<p><strong>Meet:</strong> {}</p>", escape(meet_name))?;
+    }
+    if let Some(ref venue) = metadata.venue {
+        writeln!(writer, "<p><strong>Venue:</strong> {}</p>", escape(venue))?;
+    }
+    Ok(())
+}
+
+/// Writes an event's record lines, if any
+fn write_records<W: Write>(writer: &mut W, metadata: Option<&EventMetadata>) -> Result<(), Box<dyn Error>> {
+    let Some(metadata) = metadata else {
+        return Ok(());
+    };
+    for record in &metadata.records {
+        writeln!(writer, "<p class=\"record\">{}</p>", escape(record.trim_matches('=').trim()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::tests::sample_results;
+
+    #[test]
+    fn report_matches_golden_file() {
+        let mut results = sample_results();
+        let swimmer = &mut results.individual_results[0].swimmers[1];
+        swimmer.name = "Lee & <Jones>, Amy".to_string();
+        swimmer.school = "Texas A&M".to_string();
+
+        let mut report = Vec::new();
+        write_html_report_to(&results, &OutputOptions::default(), &mut report).unwrap();
+        assert_eq!(
+            String::from_utf8(report).unwrap(),
+            include_str!("../tests/fixtures/report.html"),
+            "HTML report changed; update tests/fixtures/report.html if intended",
+        );
+    }
+}
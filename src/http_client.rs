@@ -0,0 +1,664 @@
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, COOKIE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::StatusCode;
+
+use crate::cache::HtmlCache;
+use crate::robots::RobotsRules;
+use crate::utils::decode_html;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::Instant;
+
+static SHARED_CLIENT: OnceLock<HttpClient> = OnceLock::new();
+
+const USER_AGENT: &str = concat!(
+    "realtime_results_scraper/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/ChrisHarig/realtime_results_scraper)"
+);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long idle pooled connections are kept open for reuse
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// Reads a response body as text, honoring the page's declared charset (see decode_html),
+/// and adds its size to `bytes_fetched`
+async fn response_html(response: reqwest::Response, bytes_fetched: &AtomicU64) -> Result<String, Box<dyn Error>> {
+    let content_type = response.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let bytes = response.bytes().await?;
+    bytes_fetched.fetch_add(bytes.len() as u64, Ordering::Relaxed);
+    Ok(decode_html(&bytes, content_type.as_deref()))
+}
+
+// ============================================================================
+// RETRY POLICY
+// ============================================================================
+
+/// Retry policy for transient fetch failures (5xx, timeouts, connection errors)
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first request
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each retry after
+    pub base_delay: Duration,
+    /// Upper bound on any single delay
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Returns the delay before retrying after `attempt` (1-based) failed attempts,
+    /// exponential in the attempt count with up to 50% jitter
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        delay.mul_f64(1.0 - jitter_fraction() / 2.0)
+    }
+
+    /// Checks if another attempt is allowed after `attempt` failed attempts
+    pub fn can_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+}
+
+/// Pseudo-random fraction in [0, 1) from the clock, good enough for spreading retries
+fn jitter_fraction() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    f64::from(nanos % 1000) / 1000.0
+}
+
+/// Checks if a response status is worth retrying (server errors only)
+pub fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error()
+}
+
+/// Checks if a response status means there is no page to read: anything but success or
+/// 304 Not Modified (e.g. a 404 for an event not yet posted, or a 403 login page)
+fn is_error_status(status: StatusCode) -> bool {
+    !status.is_success() && status != StatusCode::NOT_MODIFIED
+}
+
+/// Checks if a request error is worth retrying (timeouts, failed connections, and
+/// connections reset by the server)
+pub fn is_transient_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || is_connection_reset(error)
+}
+
+/// Checks if an error was caused by the connection being reset
+fn is_connection_reset(error: &(dyn Error + 'static)) -> bool {
+    let mut source = error.source();
+    while let Some(cause) = source {
+        if cause.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::ConnectionReset) {
+            return true;
+        }
+        source = cause.source();
+    }
+    false
+}
+
+/// Outcome of one request attempt, as seen by the retry loop
+enum Attempt<T> {
+    Done(T),
+    /// Failed in a way worth retrying (see is_transient_status, is_transient_error)
+    Transient(Box<dyn Error>),
+    /// Failed in a way retrying won't fix
+    Fatal(Box<dyn Error>),
+}
+
+/// Runs `send` until it succeeds, fails permanently, or the policy allows no more
+/// attempts, sleeping the policy's backoff between attempts. Returns the result and the
+/// number of retries made.
+async fn retry_with_policy<T, F, Fut>(policy: &RetryPolicy, mut send: F) -> (Result<T, Box<dyn Error>>, u32)
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Attempt<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match send().await {
+            Attempt::Done(value) => return (Ok(value), attempt - 1),
+            Attempt::Fatal(e) => return (Err(e), attempt - 1),
+            Attempt::Transient(e) if !policy.can_retry(attempt) => return (Err(e), attempt - 1),
+            Attempt::Transient(_) => {}
+        }
+        tokio::time::sleep(policy.backoff(attempt)).await;
+        attempt += 1;
+    }
+}
+
+// ============================================================================
+// RATE LIMITING
+// ============================================================================
+
+/// Enforces a minimum interval between request starts across all concurrent tasks
+#[derive(Debug, Default)]
+struct RateLimiter {
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    interval: Duration,
+    /// Lower bound from robots.txt Crawl-delay, kept when the interval is reset
+    floor: Duration,
+    next_slot: Option<Instant>,
+}
+
+impl RateLimiter {
+    fn set_interval(&self, interval: Duration) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.interval = interval;
+    }
+
+    /// Raises the interval to at least `interval` (e.g. a robots.txt Crawl-delay)
+    fn raise_interval(&self, interval: Duration) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.floor = state.floor.max(interval);
+    }
+
+    /// Reserves the next request slot and waits until it starts
+    async fn acquire(&self) {
+        let wait = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let interval = state.interval.max(state.floor);
+            if interval.is_zero() {
+                return;
+            }
+            let now = Instant::now();
+            let slot = state.next_slot.map_or(now, |next| next.max(now));
+            state.next_slot = Some(slot + interval);
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+// ============================================================================
+// CONDITIONAL REQUESTS
+// ============================================================================
+
+/// Result of a conditional fetch
+#[derive(Debug)]
+pub enum Fetched {
+    /// Page is new or changed since the last fetch
+    Modified(String),
+    /// Server reported the page unchanged (HTTP 304)
+    NotModified,
+}
+
+/// ETag/Last-Modified values remembered from a previous response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_response(response: &reqwest::Response) -> Validators {
+        let header = |name: HeaderName| {
+            response.headers().get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        Validators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+}
+
+// ============================================================================
+// HTTP CLIENT
+// ============================================================================
+
+/// HTTP client wrapper used for all page fetches; clones share one connection pool
+/// and one rate limiter
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    retry: RetryPolicy,
+    limiter: Arc<RateLimiter>,
+    cache: Option<HtmlCache>,
+    validators: Arc<Mutex<HashMap<String, Validators>>>,
+    respect_robots: bool,
+    /// robots.txt rules by origin (scheme://host:port)
+    robots: Arc<Mutex<HashMap<String, Arc<RobotsRules>>>>,
+    counters: Arc<FetchCounters>,
+}
+
+/// Running totals over every fetch made through a client and its clones
+#[derive(Debug, Default)]
+struct FetchCounters {
+    retries: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        HttpClient::new(RetryPolicy::default())
+    }
+}
+
+/// Returns a client builder with gzip, a request timeout, the crate User-Agent, and
+/// pooled keep-alive connections. HTTPS hosts that support HTTP/2 are negotiated via
+/// ALPN, so concurrent event fetches share one multiplexed connection.
+fn client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .gzip(true)
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(USER_AGENT)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .pool_max_idle_per_host(crate::DEFAULT_CONCURRENCY)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .http2_adaptive_window(true)
+}
+
+impl HttpClient {
+    /// Creates a client with the given retry policy
+    pub fn new(retry: RetryPolicy) -> HttpClient {
+        let client = client_builder()
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        HttpClient::from_client(client, retry)
+    }
+
+    /// Creates a client that sends the given headers (e.g. Cookie) with every request
+    pub fn with_headers(retry: RetryPolicy, headers: HeaderMap) -> Result<HttpClient, Box<dyn Error>> {
+        let client = client_builder()
+            .default_headers(headers)
+            .build()?;
+        Ok(HttpClient::from_client(client, retry))
+    }
+
+    /// Wraps a user-supplied client (e.g. one configured with a proxy)
+    pub fn from_client(client: reqwest::Client, retry: RetryPolicy) -> HttpClient {
+        HttpClient {
+            client,
+            retry,
+            limiter: Arc::default(),
+            cache: None,
+            validators: Arc::default(),
+            respect_robots: false,
+            robots: Arc::default(),
+            counters: Arc::default(),
+        }
+    }
+
+    /// Sets the on-disk page cache
+    pub fn set_cache(&mut self, cache: HtmlCache) {
+        self.cache = Some(cache);
+    }
+
+    /// Enables checking each host's robots.txt before fetching; disallowed pages fail
+    /// and the host's Crawl-delay raises the minimum request interval
+    pub fn set_respect_robots(&mut self, respect: bool) {
+        self.respect_robots = respect;
+    }
+
+    /// Sets the minimum delay between requests (zero disables rate limiting)
+    pub fn set_request_interval(&self, interval: Duration) {
+        self.limiter.set_interval(interval);
+    }
+
+    /// Returns the retry policy
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Returns how many requests have been retried since the client was created
+    pub fn retries(&self) -> u64 {
+        self.counters.retries.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total size of page bodies downloaded (cache hits excluded)
+    pub fn bytes_fetched(&self) -> u64 {
+        self.counters.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Fetches HTML content from a URL, reading from and writing to the cache when set
+    pub async fn fetch_html(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        let Some(ref cache) = self.cache else {
+            return self.fetch_remote(url).await;
+        };
+
+        if let Some(body) = cache.read(url) {
+            return Ok(body);
+        }
+
+        let body = self.fetch_remote(url).await?;
+        if let Err(e) = cache.write(url, &body) {
+            log::warn!("Could not cache {}: {}", url, e);
+        }
+        Ok(body)
+    }
+
+    /// Fetches a page only if it changed since the last fetch of the same URL,
+    /// sending If-None-Match/If-Modified-Since from the remembered validators
+    pub async fn fetch_conditional(&self, url: &str) -> Result<Fetched, Box<dyn Error>> {
+        let known = self.validators_for(url);
+        let response = self.send_with_retry(url, known.as_ref()).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified);
+        }
+
+        let validators = Validators::from_response(&response);
+        let body = response_html(response, &self.counters.bytes).await?;
+
+        if let Some(ref cache) = self.cache {
+            if let Err(e) = cache.write(url, &body).and_then(|_| cache.write_validators(url, &validators)) {
+                log::warn!("Could not cache {}: {}", url, e);
+            }
+        }
+        self.validators.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(url.to_string(), validators);
+
+        Ok(Fetched::Modified(body))
+    }
+
+    /// Returns remembered validators for a URL, falling back to the disk cache
+    fn validators_for(&self, url: &str) -> Option<Validators> {
+        let remembered = self.validators.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(url)
+            .cloned();
+        remembered.or_else(|| self.cache.as_ref()?.read_validators(url))
+    }
+
+    /// Fetches HTML content over the network, retrying transient failures
+    async fn fetch_remote(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        response_html(self.send_with_retry(url, None).await?, &self.counters.bytes).await
+    }
+
+    /// Returns the robots.txt rules for a URL's host, fetching them on first use.
+    /// A missing or unreadable robots.txt allows everything.
+    async fn robots_rules(&self, url: &reqwest::Url) -> Arc<RobotsRules> {
+        let origin = url.origin().ascii_serialization();
+        let known = self.robots.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&origin)
+            .cloned();
+        if let Some(rules) = known {
+            return rules;
+        }
+
+        let robots_url = format!("{}/robots.txt", origin);
+        let rules = match self.client.get(&robots_url).send().await {
+            Ok(response) if response.status().is_success() => {
+                RobotsRules::parse(&response.text().await.unwrap_or_default())
+            }
+            _ => RobotsRules::default(),
+        };
+        if let Some(delay) = rules.crawl_delay {
+            self.limiter.raise_interval(delay);
+        }
+
+        let rules = Arc::new(rules);
+        self.robots.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(origin, rules.clone());
+        rules
+    }
+
+    /// Fails if robots checking is enabled and the host's robots.txt disallows the URL
+    async fn check_robots(&self, url: &str) -> Result<(), Box<dyn Error>> {
+        if !self.respect_robots {
+            return Ok(());
+        }
+        let parsed = reqwest::Url::parse(url)?;
+        if self.robots_rules(&parsed).await.is_allowed(parsed.path()) {
+            Ok(())
+        } else {
+            Err(format!("Disallowed by robots.txt: {}", url).into())
+        }
+    }
+
+    /// Sends a GET request, retrying transient failures. Error statuses other than 304
+    /// fail, so their bodies are never parsed or cached.
+    async fn send_with_retry(
+        &self,
+        url: &str,
+        validators: Option<&Validators>,
+    ) -> Result<reqwest::Response, Box<dyn Error>> {
+        self.check_robots(url).await?;
+
+        let (result, retries) = retry_with_policy(&self.retry, move || async move {
+            self.limiter.acquire().await;
+
+            let mut request = self.client.get(url);
+            if let Some(validators) = validators {
+                if let Some(ref etag) = validators.etag {
+                    request = request.header(IF_NONE_MATCH, etag);
+                }
+                if let Some(ref last_modified) = validators.last_modified {
+                    request = request.header(IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+
+            match request.send().await {
+                Ok(response) if is_transient_status(response.status()) => {
+                    Attempt::Transient(format!("HTTP {} from {}", response.status(), url).into())
+                }
+                Ok(response) if is_error_status(response.status()) => {
+                    Attempt::Fatal(format!("HTTP {} from {}", response.status(), url).into())
+                }
+                Ok(response) => Attempt::Done(response),
+                Err(e) if is_transient_error(&e) => Attempt::Transient(e.into()),
+                Err(e) => Attempt::Fatal(e.into()),
+            }
+        }).await;
+
+        self.counters.retries.fetch_add(u64::from(retries), Ordering::Relaxed);
+        result
+    }
+}
+
+// ============================================================================
+// REQUEST HEADERS
+// ============================================================================
+
+/// Builds request headers from an optional cookie and "Name: Value" header strings
+pub fn build_headers(cookie: Option<&str>, headers: &[String]) -> Result<HeaderMap, Box<dyn Error>> {
+    let mut map = HeaderMap::new();
+
+    for header in headers {
+        let (name, value) = header.split_once(':')
+            .ok_or_else(|| format!("Invalid header (expected \"Name: Value\"): {}", header))?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes())?;
+        let value = HeaderValue::from_str(value.trim())?;
+        map.append(name, value);
+    }
+
+    if let Some(cookie) = cookie {
+        let mut value = HeaderValue::from_str(cookie)?;
+        value.set_sensitive(true);
+        map.insert(COOKIE, value);
+    }
+
+    Ok(map)
+}
+
+// ============================================================================
+// SHARED CLIENT
+// ============================================================================
+
+/// Sets the client used by all fetches; must be called before the first fetch.
+/// Returns the client back if one was already set.
+pub fn set_shared_client(client: HttpClient) -> Result<(), HttpClient> {
+    SHARED_CLIENT.set(client)
+}
+
+/// Returns the shared client, creating a default one on first use
+pub fn shared_client() -> &'static HttpClient {
+    SHARED_CLIENT.get_or_init(HttpClient::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt;
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+        }
+    }
+
+    /// Mocked transport: returns the scripted outcomes in order, counting calls
+    async fn run_script(policy: &RetryPolicy, script: Vec<&'static str>) -> (Result<&'static str, Box<dyn Error>>, u32, usize) {
+        let mut outcomes = script.into_iter();
+        let mut calls = 0;
+        let (result, retries) = retry_with_policy(policy, || {
+            calls += 1;
+            let outcome = match outcomes.next().expect("transport called too often") {
+                "503" => Attempt::Transient("HTTP 503".into()),
+                "404" => Attempt::Fatal("HTTP 404".into()),
+                body => Attempt::Done(body),
+            };
+            async move { outcome }
+        }).await;
+        (result, retries, calls)
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried_until_success() {
+        let (result, retries, calls) = run_script(&fast_policy(3), vec!["503", "503", "ok"]).await;
+        assert_eq!(result.unwrap(), "ok");
+        assert_eq!((retries, calls), (2, 3));
+    }
+
+    #[tokio::test]
+    async fn retries_stop_at_max_attempts() {
+        let (result, retries, calls) = run_script(&fast_policy(2), vec!["503", "503", "ok"]).await;
+        assert_eq!(result.unwrap_err().to_string(), "HTTP 503");
+        assert_eq!((retries, calls), (1, 2));
+    }
+
+    #[tokio::test]
+    async fn fatal_failures_are_not_retried() {
+        let (result, retries, calls) = run_script(&fast_policy(3), vec!["404", "ok"]).await;
+        assert_eq!(result.unwrap_err().to_string(), "HTTP 404");
+        assert_eq!((retries, calls), (0, 1));
+
+        let (_, _, calls) = run_script(&RetryPolicy::none(), vec!["503", "ok"]).await;
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max_delay() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(400),
+        };
+        for (attempt, full) in [(1, 100), (2, 200), (3, 400), (4, 400), (40, 400)] {
+            let delay = policy.backoff(attempt);
+            assert!(delay <= Duration::from_millis(full), "attempt {}: {:?}", attempt, delay);
+            assert!(delay >= Duration::from_millis(full / 2), "attempt {}: {:?}", attempt, delay);
+        }
+    }
+
+    #[test]
+    fn error_statuses_are_not_read_as_pages() {
+        assert!(is_error_status(StatusCode::NOT_FOUND));
+        assert!(is_error_status(StatusCode::UNAUTHORIZED));
+        assert!(is_error_status(StatusCode::FORBIDDEN));
+        assert!(!is_error_status(StatusCode::OK));
+        assert!(!is_error_status(StatusCode::NOT_MODIFIED));
+    }
+
+    #[test]
+    fn only_server_errors_are_transient_statuses() {
+        assert!(is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_transient_status(StatusCode::BAD_GATEWAY));
+        assert!(!is_transient_status(StatusCode::NOT_FOUND));
+        assert!(!is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+    }
+
+    #[derive(Debug)]
+    struct Wrapped(std::io::Error);
+
+    impl fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "request failed")
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn connection_reset_is_found_in_error_source() {
+        let reset = Wrapped(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        let denied = Wrapped(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert!(is_connection_reset(&reset));
+        assert!(!is_connection_reset(&denied));
+    }
+
+    #[tokio::test]
+    async fn invalid_request_errors_are_not_transient() {
+        let error = reqwest::Client::new().get("not a url").send().await.unwrap_err();
+        assert!(!is_transient_error(&error));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limiter_spaces_concurrent_request_starts() {
+        let interval = Duration::from_millis(250);
+        let limiter = Arc::new(RateLimiter::default());
+        limiter.set_interval(interval);
+
+        let tasks: Vec<_> = (0..5)
+            .map(|_| {
+                let limiter = Arc::clone(&limiter);
+                tokio::spawn(async move {
+                    limiter.acquire().await;
+                    Instant::now()
+                })
+            })
+            .collect();
+        let mut starts = Vec::new();
+        for task in tasks {
+            starts.push(task.await.unwrap());
+        }
+        starts.sort();
+
+        for pair in starts.windows(2) {
+            assert!(pair[1] - pair[0] >= interval, "starts {:?} apart", pair[1] - pair[0]);
+        }
+    }
+}
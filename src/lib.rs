@@ -1,37 +1,382 @@
+pub mod athletes;
+pub mod cache;
+pub mod diff;
 pub mod event_handler;
+pub mod html;
+pub mod http_client;
 pub mod meet_handler;
 pub mod metadata;
 pub mod output;
+pub mod progress;
 pub mod relay_handler;
+pub mod robots;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod time;
 pub mod utils;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
 
+use std::collections::HashSet;
 use std::error::Error;
-use futures::future::join_all;
+use std::fmt;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::time::Duration;
+use futures::stream::{self, Stream, StreamExt};
+use scraper::{Html, Selector};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use url::Url;
 
+use event_handler::event_text;
+use http_client::shared_client;
 use metadata::{parse_event_metadata, parse_race_info};
-use utils::{fetch_html, extract_session_from_url};
+use utils::{decode_html, fetch_html, extract_session_from_url, hash_content, session_label};
 
 // ============================================================================
 // PUBLIC API RE-EXPORTS
 // ============================================================================
 
-pub use meet_handler::{parse_meet_index, Meet, Event};
-pub use metadata::{EventMetadata, RaceInfo};
-pub use output::{print_individual_results, write_individual_csv, write_relay_csv, print_relay_results, write_metadata_csv, write_results_to_folders, OutputOptions};
-pub use event_handler::{parse_individual_event_html, EventResults, Swimmer, Split};
-pub use relay_handler::{parse_relay_event_html, RelayResults, RelayTeam, RelaySwimmer};
-pub use utils::{generate_unique_id, sanitize_name};
+pub use meet_handler::{parse_meet_index, parse_meet_index_html, parse_session_index, parse_meet_sessions, Meet, Event, Session};
+pub use metadata::{parse_meet_dates, EventMetadata, RaceInfo};
+pub use output::{print_individual_results, write_individual_csv, write_individual_csv_to, write_relay_csv, write_relay_csv_to, print_relay_results, write_metadata_csv, write_metadata_csv_to, write_splits_csv, write_relay_splits_csv, write_results_to_folders, write_combined_csv, write_meet_summary_csv, write_exchange_report_csv, write_results_ndjson, write_results_json, write_results_json_to, write_results_jsonl, write_markdown_report, data_file, meet_output_name, meet_output_path, prepare_output_dir, resolve_output_conflict, FolderNaming, FolderWriter, Manifest, ManifestEvent, ManifestFile, NameFormat, OnConflict, OutputOptions, RelaySplitLayout, SortKey, TimeFormat};
+pub use athletes::{write_athletes_csv, Athlete, AthleteKey, AthleteSwim};
+pub use cache::HtmlCache;
+pub use diff::{diff_results, EntryChange, EventDiff, ResultsDiff};
+pub use html::{write_html_report, write_html_report_to};
+pub use robots::RobotsRules;
+#[cfg(feature = "sqlite")]
+pub use sqlite::write_results_sqlite;
+#[cfg(feature = "xlsx")]
+pub use xlsx::write_results_xlsx;
+pub use progress::{ProgressCallback, ProgressEvent};
+pub use http_client::{build_headers, set_shared_client, Fetched, HttpClient, RetryPolicy, Validators};
+pub use event_handler::{parse_individual_event_html, parse_individual_event_pages, merge_prelim_final, standard_description, STANDARD_INDICATORS, EventResults, FinalType, Swimmer, Split, SplitWarning};
+pub use relay_handler::{parse_relay_event_html, RelayResults, RelayTeam, RelaySwimmer, CLOSE_EXCHANGE_LIMIT, EARLY_TAKEOFF_LIMIT};
+pub use time::{SwimTime, ParseSwimTimeError};
+pub use utils::{generate_unique_id, hash_content, parse_time_to_centiseconds, sanitize_name, name_first_last, name_last_first, SessionType};
 
 // ============================================================================
 // PARSED RESULTS
 // ============================================================================
 
 /// Complete parsed results with optional meet info
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParsedResults {
     pub individual_results: Vec<EventResults>,
     pub relay_results: Vec<RelayResults>,
     pub meet_title: Option<String>,
+    /// Events that could not be fetched or parsed
+    pub errors: Vec<EventError>,
+}
+
+/// Counts from one process_meet run (see process_meet_with_stats)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScrapeStats {
+    /// Event pages fetched or attempted
+    pub events_attempted: usize,
+    /// Event pages parsed, including unchanged pages skipped with only_changed
+    pub events_succeeded: usize,
+    pub events_failed: usize,
+    /// Individual swimmers plus relay team swimmers
+    pub swimmers_parsed: usize,
+    /// Requests retried after transient failures
+    pub fetch_retries: u64,
+    /// Page bytes downloaded, excluding cache hits
+    pub bytes_fetched: u64,
+}
+
+/// Failure to fetch or parse one event of a meet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventError {
+    pub event_name: String,
+    pub url: String,
+    pub session: char,
+    pub error: String,
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.event_name, self.url, self.error)
+    }
+}
+
+impl Error for EventError {}
+
+/// Errors raised before any page is fetched
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScraperError {
+    /// The URL can't be scraped; `reason` says what is wrong and how to fix it
+    InvalidUrl { url: String, reason: String },
+}
+
+impl fmt::Display for ScraperError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScraperError::InvalidUrl { url, reason } => write!(f, "invalid URL \"{}\": {}", url, reason),
+        }
+    }
+}
+
+impl Error for ScraperError {}
+
+/// Borrowed individual or relay event from ParsedResults
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventRef<'a> {
+    Individual(&'a EventResults),
+    Relay(&'a RelayResults),
+}
+
+impl ParsedResults {
+    /// Returns the first event (individual or relay) with this event number and gender.
+    /// Men's and women's events often share a number, so pass the gender when both swim
+    pub fn event_by_number(&self, number: u32, gender: Option<&str>) -> Option<EventRef<'_>> {
+        self.events_by_number(number).find(|event| event.has_gender(gender))
+    }
+
+    /// Returns the event with this event number and gender from one session ('P' or 'F')
+    pub fn event_by_number_session(&self, number: u32, gender: Option<&str>, session: char) -> Option<EventRef<'_>> {
+        self.events_by_number(number).find(|event| event.has_gender(gender) && event.session() == session)
+    }
+
+    /// Returns every event (all sessions and genders) with this event number
+    pub fn events_by_number(&self, number: u32) -> impl Iterator<Item = EventRef<'_>> {
+        let has_number = move |race_info: Option<&RaceInfo>| race_info.map(|i| i.event_number) == Some(number);
+        self.individual_results.iter()
+            .filter(move |r| has_number(r.race_info.as_ref()))
+            .map(EventRef::Individual)
+            .chain(self.relay_results.iter()
+                .filter(move |r| has_number(r.race_info.as_ref()))
+                .map(EventRef::Relay))
+    }
+
+    /// Appends each relay's leadoff swims as an individual event (see
+    /// RelayResults::leadoff_event), marked with `leadoff`
+    pub fn include_leadoffs(&mut self) {
+        let leadoffs: Vec<EventResults> = self.relay_results.iter()
+            .filter_map(RelayResults::leadoff_event)
+            .collect();
+        self.individual_results.extend(leadoffs);
+    }
+
+    /// Rewrites every seed, final, split, and relay leg time in the given format, keeping
+    /// record flags on the time and leaving status strings (DQ, NS, NT) as they are
+    pub fn format_times(&mut self, format: TimeFormat) {
+        if format == TimeFormat::Raw {
+            return;
+        }
+        let reformat = |time: &mut String| *time = format.apply(time);
+        for event in &mut self.individual_results {
+            for swimmer in &mut event.swimmers {
+                swimmer.seed_time.iter_mut().for_each(reformat);
+                reformat(&mut swimmer.final_time);
+                swimmer.splits.iter_mut().chain(&mut swimmer.prelim_splits)
+                    .for_each(|split| reformat(&mut split.time));
+            }
+        }
+        for event in &mut self.relay_results {
+            for team in &mut event.teams {
+                team.seed_time.iter_mut().for_each(reformat);
+                reformat(&mut team.final_time);
+                team.splits.iter_mut().for_each(|split| reformat(&mut split.time));
+                team.swimmers.iter_mut().filter_map(|s| s.leg_time.as_mut()).for_each(reformat);
+            }
+        }
+    }
+
+    /// Returns every event's parse warnings, each prefixed with its event and session
+    pub fn warnings(&self) -> Vec<String> {
+        let individual = self.individual_results.iter()
+            .map(|r| (&r.event_name, r.session, &r.warnings));
+        let relay = self.relay_results.iter()
+            .map(|r| (&r.event_name, r.session, &r.warnings));
+        individual.chain(relay)
+            .flat_map(|(name, session, warnings)| warnings.iter()
+                .map(move |w| format!("{} ({}): {}", name.trim(), session_label(session), w)))
+            .collect()
+    }
+}
+
+impl EventRef<'_> {
+    /// Returns the event's session ('P' or 'F')
+    pub fn session(&self) -> char {
+        match self {
+            EventRef::Individual(result) => result.session,
+            EventRef::Relay(result) => result.session,
+        }
+    }
+
+    /// Checks the event's gender against `gender`; None matches any event
+    fn has_gender(&self, gender: Option<&str>) -> bool {
+        let race_info = match self {
+            EventRef::Individual(result) => result.race_info.as_ref(),
+            EventRef::Relay(result) => result.race_info.as_ref(),
+        };
+        gender.is_none_or(|gender| {
+            race_info
+                .and_then(|info| info.gender.as_deref())
+                .is_some_and(|g| g.eq_ignore_ascii_case(gender))
+        })
+    }
+}
+
+// ============================================================================
+// SCRAPE OPTIONS
+// ============================================================================
+
+/// Default number of event pages fetched at the same time
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Which sessions of each event are fetched
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SessionFilter {
+    #[default]
+    All,
+    FinalsOnly,
+    PrelimsOnly,
+}
+
+impl SessionFilter {
+    /// Checks if an event page of the given session (P/F) should be fetched
+    pub fn includes(&self, session: char) -> bool {
+        match self {
+            SessionFilter::All => true,
+            SessionFilter::FinalsOnly => session == 'F',
+            SessionFilter::PrelimsOnly => session == 'P',
+        }
+    }
+}
+
+/// Restricts which events of a meet are fetched; empty fields match every event
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Event number ranges (e.g. 1..=1, 5..=9)
+    pub numbers: Vec<RangeInclusive<u32>>,
+    /// Case-insensitive substring of the stroke (e.g. "breast")
+    pub stroke: Option<String>,
+    /// Gender, matched case-insensitively (e.g. "women")
+    pub gender: Option<String>,
+}
+
+impl EventFilter {
+    /// Parses an event number list like "1,5-9,21" into ranges
+    pub fn parse_numbers(spec: &str) -> Result<Vec<RangeInclusive<u32>>, Box<dyn Error>> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let invalid = || format!("Invalid event number or range: {}", part);
+                let (start, end) = part.split_once('-').unwrap_or((part, part));
+                let start: u32 = start.trim().parse().map_err(|_| invalid())?;
+                let end: u32 = end.trim().parse().map_err(|_| invalid())?;
+                if start > end {
+                    return Err(invalid().into());
+                }
+                Ok(start..=end)
+            })
+            .collect()
+    }
+
+    /// Checks if an index event passes the number, stroke, and gender filters
+    pub fn matches(&self, event: &Event) -> bool {
+        if !self.numbers.is_empty() && !self.numbers.iter().any(|r| r.contains(&event.number)) {
+            return false;
+        }
+        if self.stroke.is_none() && self.gender.is_none() {
+            return true;
+        }
+
+        let race_info = event.race_info();
+        let stroke_matches = self.stroke.as_ref().is_none_or(|stroke| {
+            let stroke = stroke.to_lowercase();
+            race_info.as_ref()
+                .and_then(|info| info.stroke.as_ref())
+                .unwrap_or(&event.name)
+                .to_lowercase()
+                .contains(&stroke)
+        });
+        let gender_matches = self.gender.as_ref().is_none_or(|gender| {
+            race_info.as_ref()
+                .and_then(|info| info.gender.as_ref())
+                .is_some_and(|g| g.eq_ignore_ascii_case(gender))
+        });
+
+        stroke_matches && gender_matches
+    }
+}
+
+/// Restricts batch scraping to meets held within a date window; unset bounds are open
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DateRange {
+    /// Earliest meet day to include
+    pub since: Option<NaiveDate>,
+    /// Latest meet day to include
+    pub until: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// Checks if neither bound is set
+    pub fn is_unbounded(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    /// Checks if a meet running from `start` to `end` overlaps the range
+    pub fn includes(&self, start: NaiveDate, end: NaiveDate) -> bool {
+        self.since.is_none_or(|since| end >= since) && self.until.is_none_or(|until| start <= until)
+    }
+}
+
+/// Configuration for fetching and processing a meet
+#[derive(Debug, Clone)]
+pub struct ScrapeOptions {
+    /// Maximum number of event pages fetched at the same time
+    pub concurrency: usize,
+    /// Only process events linked from the session index entry with this name
+    pub session: Option<String>,
+    /// Minimum delay between outgoing requests, shared across concurrent fetches
+    pub request_interval: Duration,
+    /// Only return events whose pages changed since the previous scrape in this
+    /// process (uses ETag/Last-Modified), so process_meet can be polled
+    pub only_changed: bool,
+    /// Restricts which of each event's prelims and finals pages are fetched
+    pub session_filter: SessionFilter,
+    /// Restricts which events are fetched by number, stroke, and gender
+    pub event_filter: EventFilter,
+    /// Called as event pages are fetched and parsed
+    pub progress: Option<ProgressCallback>,
+    /// Keep each event's raw results text in `raw_text`, for diagnosing parser issues
+    pub keep_raw: bool,
+    /// Meets outside this date window are skipped by process_meets (see meet_in_date_range)
+    pub date_range: DateRange,
+    /// Only fetch the first N events of a meet, in event-number order (prelims and finals
+    /// pages of an event count once)
+    pub limit: Option<usize>,
+}
+
+impl ScrapeOptions {
+    /// Sets a callback invoked with progress events while a meet is processed
+    pub fn on_progress(&mut self, callback: impl Fn(ProgressEvent) + Send + Sync + 'static) {
+        self.progress = Some(ProgressCallback::new(callback));
+    }
+}
+
+impl Default for ScrapeOptions {
+    fn default() -> Self {
+        ScrapeOptions {
+            concurrency: DEFAULT_CONCURRENCY,
+            session: None,
+            request_interval: Duration::ZERO,
+            only_changed: false,
+            session_filter: SessionFilter::All,
+            event_filter: EventFilter::default(),
+            progress: None,
+            keep_raw: false,
+            date_range: DateRange::default(),
+            limit: None,
+        }
+    }
 }
 
 // ============================================================================
@@ -45,9 +390,40 @@ pub enum UrlType {
     Event,
 }
 
-/// Detects if a URL points to a meet index or individual event
+/// Checks that a URL is an http(s) URL with a host and, for event pages, that the
+/// session can be read from its file name (e.g. 240327F003.htm), returning its type
+pub fn validate_url(url: &str) -> Result<UrlType, ScraperError> {
+    let invalid = |reason: &str| ScraperError::InvalidUrl { url: url.to_string(), reason: reason.to_string() };
+
+    let parsed = Url::parse(url)
+        .map_err(|e| invalid(&format!("{} (expected e.g. https://swimmeetresults.tech/2025-Big-Ten/)", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid("only http and https URLs are supported"));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(invalid("the URL has no host"));
+    }
+
+    let filename = parsed.path().trim_end_matches('/').rsplit('/').next().unwrap_or("");
+    if filename.to_ascii_lowercase().ends_with(".html") {
+        return Err(invalid("event pages end in .htm, not .html; check the file name"));
+    }
+
+    let url_type = detect_url_type(url);
+    if url_type == UrlType::Event && extract_session_from_url(url) == SessionType::Unknown {
+        return Err(invalid(
+            "event file names carry the session and event number (e.g. 240327F003.htm); \
+             link to an event page, or to the meet index instead",
+        ));
+    }
+    Ok(url_type)
+}
+
+/// Detects if a URL points to a meet index or individual event, ignoring any query
+/// string or fragment and the extension's case
 pub fn detect_url_type(url: &str) -> UrlType {
-    if url.trim_end_matches('/').ends_with(".htm") {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    if path.trim_end_matches('/').to_ascii_lowercase().ends_with(".htm") {
         UrlType::Event
     } else {
         UrlType::Meet
@@ -59,28 +435,163 @@ pub fn detect_url_type(url: &str) -> UrlType {
 // ============================================================================
 
 /// Parsed event result (individual or relay)
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ParsedEvent {
     Individual(EventResults),
     Relay(RelayResults),
 }
 
+impl ParsedEvent {
+    /// Returns the hash of the event's results text
+    pub fn content_hash(&self) -> &str {
+        match self {
+            ParsedEvent::Individual(result) => &result.content_hash,
+            ParsedEvent::Relay(result) => &result.content_hash,
+        }
+    }
+
+    /// Infers finals from places on an individual finals page (see
+    /// EventResults::infer_final_types)
+    fn infer_final_types(&mut self) {
+        if let ParsedEvent::Individual(result) = self {
+            result.infer_final_types();
+        }
+    }
+
+    /// Stores the raw results text the parser saw on the event: the page's `<pre>` block
+    /// followed by the body of each continuation page (see event_text)
+    fn keep_raw_text(&mut self, html: &str, continuations: &[String]) {
+        let raw_text = event_text(html, continuations);
+        match self {
+            ParsedEvent::Individual(result) => result.raw_text = raw_text,
+            ParsedEvent::Relay(result) => result.raw_text = raw_text,
+        }
+    }
+}
+
 /// Fetches and parses a single event URL, dispatching to individual or relay parser
 pub async fn process_event(url: &str, session: char) -> Result<ParsedEvent, Box<dyn Error>> {
     let html = fetch_html(url).await?;
-    let metadata = parse_event_metadata(&html).ok_or_else(|| {
-        eprintln!("Error: Could not parse event metadata from page");
-        "Could not find event metadata in page"
-    })?;
+    parse_event_html(&html, session)
+}
+
+/// Fetches and parses a single event URL only if the page changed since it was last
+/// fetched (via ETag/Last-Modified), returning None when unchanged
+pub async fn process_event_if_modified(url: &str, session: char) -> Result<Option<ParsedEvent>, Box<dyn Error>> {
+    match shared_client().fetch_conditional(url).await? {
+        Fetched::Modified(html) => parse_event_html(&html, session).map(Some),
+        Fetched::NotModified => Ok(None),
+    }
+}
+
+/// Fetches an event URL and parses it only if its results text hashes differently from
+/// `last_hash` (see ParsedEvent::content_hash), returning None when unchanged
+pub async fn parse_if_changed(url: &str, last_hash: Option<&str>) -> Result<Option<ParsedEvent>, Box<dyn Error>> {
+    let html = fetch_html(url).await?;
+    let continuations = fetch_continuation_pages(url, &html).await?;
+    if let (Some(last_hash), Some(text)) = (last_hash, event_text(&html, &continuations)) {
+        if hash_content(&text) == last_hash {
+            return Ok(None);
+        }
+    }
+
+    let session = extract_session_from_url(url);
+    parse_event_pages(&html, &continuations, session.as_char()).map(Some)
+}
+
+/// Most pages followed for one event (the first page plus continuations)
+const MAX_EVENT_PAGES: usize = 20;
+
+/// Lowercased file name of a URL without its extension (e.g. "240327f003")
+fn page_stem(url: &Url) -> String {
+    let filename = url.path().trim_end_matches('/').rsplit('/').next().unwrap_or("");
+    let stem = filename.rsplit_once('.').map_or(filename, |(stem, _)| stem);
+    stem.to_ascii_lowercase()
+}
+
+/// Finds a "Next page", "Continued", or ">>" link on an event page. Only links to pages
+/// named after the event's first page (e.g. 240327F003_2.htm) count, so links to other
+/// events are never followed.
+fn next_page_link(html: &str, page_url: &str, first_url: &Url) -> Option<String> {
+    let base = Url::parse(page_url).ok()?;
+    let first_stem = page_stem(first_url);
+    let document = Html::parse_document(html);
+    let link_selector = Selector::parse("a[href]").unwrap();
+
+    document.select(&link_selector).find_map(|link| {
+        let text = link.text().collect::<String>().trim().to_lowercase();
+        let is_next = text.contains("next") || text.contains("continued") || text.starts_with("page ")
+            || text == ">" || text == ">>";
+        if !is_next {
+            return None;
+        }
+        let mut target = base.join(link.value().attr("href")?).ok()?;
+        target.set_fragment(None);
+        let stem = page_stem(&target);
+        (stem.starts_with(&first_stem) && stem != first_stem).then(|| target.to_string())
+    })
+}
+
+/// Follows an event page's continuation links, returning the HTML of each further page.
+/// Stops at a page already visited or after MAX_EVENT_PAGES pages.
+async fn fetch_continuation_pages(url: &str, html: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    follow_continuations(url, html, |link| async move { fetch_html(&link).await }).await
+}
+
+/// Follows continuation links from an event's first page, fetching each with `fetch`
+async fn follow_continuations<F, Fut>(url: &str, html: &str, mut fetch: F) -> Result<Vec<String>, Box<dyn Error>>
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<String, Box<dyn Error>>>,
+{
+    let Ok(first_url) = Url::parse(url) else {
+        return Ok(Vec::new());
+    };
+    let mut visited: HashSet<String> = HashSet::from([first_url.to_string()]);
+    let mut pages: Vec<String> = Vec::new();
+    let mut next = next_page_link(html, url, &first_url);
+
+    while let Some(link) = next {
+        if !visited.insert(link.clone()) {
+            break;
+        }
+        if pages.len() + 1 >= MAX_EVENT_PAGES {
+            log::warn!("Stopped following {} after {} pages", url, MAX_EVENT_PAGES);
+            break;
+        }
+        let page = fetch(link.clone()).await?;
+        next = next_page_link(&page, &link, &first_url);
+        pages.push(page);
+    }
+
+    if !pages.is_empty() {
+        log::info!("Read {} continuation pages for {}", pages.len(), url);
+    }
+    Ok(pages)
+}
+
+/// Parses already-fetched event page HTML, dispatching to individual or relay parser
+pub fn parse_event_html(html: &str, session: char) -> Result<ParsedEvent, Box<dyn Error>> {
+    parse_event_pages(html, &[], session)
+}
+
+/// Parses an event page along with the HTML of its continuation pages (individual events
+/// only; relay results are read from the first page)
+fn parse_event_pages(html: &str, continuations: &[String], session: char) -> Result<ParsedEvent, Box<dyn Error>> {
+    let metadata = parse_event_metadata(html).ok_or("Could not find event metadata in page")?;
     let event_name = metadata.event_headline.clone();
     let race_info = parse_race_info(&event_name);
     let is_relay = race_info.as_ref().is_some_and(|info| info.is_relay);
 
     if is_relay {
-        let result = parse_relay_event_html(&html, &event_name, session, Some(metadata), race_info)?;
+        let mut result = parse_relay_event_html(html, &event_name, session, Some(metadata), race_info)?;
+        // Hash every page, as for individual events, so parse_if_changed can compare it
+        if let Some(text) = event_text(html, continuations) {
+            result.content_hash = hash_content(&text);
+        }
         Ok(ParsedEvent::Relay(result))
     } else {
-        let result = parse_individual_event_html(&html, &event_name, session, Some(metadata), race_info)?;
+        let result = parse_individual_event_pages(html, continuations, &event_name, session, Some(metadata), race_info)?;
         Ok(ParsedEvent::Individual(result))
     }
 }
@@ -89,81 +600,640 @@ pub async fn process_event(url: &str, session: char) -> Result<ParsedEvent, Box<
 // MEET PROCESSING
 // ============================================================================
 
-/// Fetches and parses all events in a meet, returning individual and relay results with meet info
-pub async fn process_meet(url: &str) -> Result<ParsedResults, Box<dyn Error>> {
-    let meet = parse_meet_index(url).await?;
-    let meet_title = meet.title.clone();
+/// (event name, link, session, has prelims) of one event page to fetch
+type EventTask = (String, String, char, bool);
 
-    let event_tasks: Vec<(String, String, char)> = meet.events.iter()
-        .flat_map(|(_, event)| {
+/// Lists the page to fetch for every event page in a meet in event-number order,
+/// optionally restricted to the events of one session index entry, to prelims or finals,
+/// to events passing an event filter, and to the first `limit` events
+fn meet_event_tasks(
+    meet: &Meet,
+    session: Option<&Session>,
+    filter: SessionFilter,
+    events: &EventFilter,
+    limit: Option<usize>,
+) -> Vec<EventTask> {
+    let mut matching: Vec<&Event> = meet.events.values()
+        .filter(|event| events.matches(event))
+        .collect();
+    matching.sort_by_key(|event| event.number);
+
+    matching.into_iter()
+        .map(|event| {
             [(&event.prelims_link, 'P'), (&event.finals_link, 'F')]
                 .into_iter()
-                .filter_map(|(link, session)| {
-                    link.as_ref().map(|l| (event.name.clone(), l.clone(), session))
+                .filter(|(_, s)| filter.includes(*s))
+                .filter(|(_, s)| session.is_none_or(|sess| sess.contains(event.number, event.gender.as_deref(), *s)))
+                .filter_map(|(link, s)| {
+                    link.as_ref().map(|l| (event.name.clone(), l.clone(), s, event.prelims_link.is_some()))
                 })
+                .collect::<Vec<_>>()
         })
-        .collect();
+        .filter(|pages| !pages.is_empty())
+        .take(limit.unwrap_or(usize::MAX))
+        .flatten()
+        .collect()
+}
+
+/// Fetches the meet index (and session index when `options.session` is set), returning
+/// the meet title and the event pages to fetch
+async fn prepare_meet(
+    url: &str,
+    options: &ScrapeOptions,
+) -> Result<(Option<String>, Vec<EventTask>), Box<dyn Error>> {
+    shared_client().set_request_interval(options.request_interval);
+
+    let mut meet = parse_meet_index(url).await?;
+
+    if options.session.is_some() {
+        meet.set_sessions(parse_meet_sessions(url).await?);
+    }
+    let session = match options.session.as_deref() {
+        Some(name) => Some(meet.find_session(name)
+            .ok_or_else(|| format!("Could not find session: {}", name))?),
+        None => None,
+    };
+
+    let event_tasks = meet_event_tasks(&meet, session, options.session_filter, &options.event_filter, options.limit);
+    Ok((meet.title, event_tasks))
+}
+
+/// Fetches and parses one event page, reporting once the page is downloaded;
+/// returns None for unchanged pages when `only_changed` is set
+async fn fetch_and_parse_event(
+    event_name: &str,
+    link: &str,
+    session: char,
+    has_prelims: bool,
+    only_changed: bool,
+    keep_raw: bool,
+    progress: Option<&ProgressCallback>,
+) -> Result<Option<ParsedEvent>, Box<dyn Error>> {
+    let html = if only_changed {
+        match shared_client().fetch_conditional(link).await? {
+            Fetched::Modified(html) => html,
+            Fetched::NotModified => return Ok(None),
+        }
+    } else {
+        fetch_html(link).await?
+    };
+
+    let continuations = fetch_continuation_pages(link, &html).await?;
+    progress::report(progress, ProgressEvent::EventFetched { name: event_name.to_string(), session });
+    let mut event = parse_event_pages(&html, &continuations, session)?;
+    if has_prelims {
+        event.infer_final_types();
+    }
+    if keep_raw {
+        event.keep_raw_text(&html, &continuations);
+    }
+    Ok(Some(event))
+}
+
+/// Fetches event pages concurrently, yielding (task index, result) in completion order;
+/// unchanged pages yield Ok(None) when `only_changed` is set
+fn event_stream(
+    event_tasks: Vec<EventTask>,
+    options: &ScrapeOptions,
+) -> impl Stream<Item = (usize, Result<Option<ParsedEvent>, EventError>)> {
+    let only_changed = options.only_changed;
+    let keep_raw = options.keep_raw;
+    let progress = options.progress.clone();
+    progress::report(progress.as_ref(), ProgressEvent::Started { total: event_tasks.len() });
+
+    stream::iter(event_tasks.into_iter().enumerate())
+        .map(move |(i, (event_name, link, session, has_prelims))| {
+            let progress = progress.clone();
+            async move {
+                let result = fetch_and_parse_event(&event_name, &link, session, has_prelims, only_changed, keep_raw, progress.as_ref()).await;
+
+                let name = event_name.clone();
+                progress::report(progress.as_ref(), match result {
+                    Ok(Some(_)) => ProgressEvent::EventParsed { name, session },
+                    Ok(None) => ProgressEvent::EventUnchanged { name, session },
+                    Err(ref e) => ProgressEvent::EventFailed { name, session, error: e.to_string() },
+                });
+
+                let result = result.map_err(|e| EventError {
+                    event_name,
+                    url: link,
+                    session,
+                    error: e.to_string(),
+                });
+                (i, result)
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1))
+}
+
+/// Fetches and parses all events in a meet, yielding each event as soon as it is parsed
+///
+/// Events arrive in completion order, not index order. Options are applied as in
+/// process_meet; with `options.only_changed`, unchanged events are skipped.
+pub async fn process_meet_stream(
+    url: &str,
+    options: &ScrapeOptions,
+) -> Result<impl Stream<Item = Result<ParsedEvent, EventError>>, Box<dyn Error>> {
+    validate_url(url)?;
+    let (_, event_tasks) = prepare_meet(url, options).await?;
+    Ok(event_stream(event_tasks, options).filter_map(|(_, result)| async move { result.transpose() }))
+}
+
+/// Fetches and parses all events in a meet, returning individual and relay results with meet info
+///
+/// At most `options.concurrency` event pages are fetched at once. When `options.session` is set,
+/// only events linked from the matching session index page are processed.
+pub async fn process_meet(url: &str, options: &ScrapeOptions) -> Result<ParsedResults, Box<dyn Error>> {
+    Ok(process_meet_with_stats(url, options).await?.0)
+}
+
+/// Like process_meet, also returning counts of events, swimmers, retries, and bytes for
+/// monitoring. Retries and bytes are read from the shared client, so they include other
+/// fetches running at the same time (e.g. other meets in process_meets).
+pub async fn process_meet_with_stats(
+    url: &str,
+    options: &ScrapeOptions,
+) -> Result<(ParsedResults, ScrapeStats), Box<dyn Error>> {
+    let client = shared_client();
+    let (retries_before, bytes_before) = (client.retries(), client.bytes_fetched());
+
+    let (meet_title, event_tasks) = prepare_meet(url, options).await?;
+    let events_attempted = event_tasks.len();
+
+    // Results arrive in completion order; sort back into task order
+    let mut results: Vec<_> = event_stream(event_tasks, options).collect().await;
+    results.sort_by_key(|(i, _)| *i);
+
+    let mut individual_results = Vec::new();
+    let mut relay_results = Vec::new();
+    let mut errors = Vec::new();
 
-    let futures: Vec<_> = event_tasks.iter()
-        .map(|(_, link, session)| process_event(link, *session))
+    for (_, result) in results {
+        match result {
+            Ok(Some(ParsedEvent::Individual(er))) => individual_results.push(er),
+            Ok(Some(ParsedEvent::Relay(rr))) => relay_results.push(rr),
+            Ok(None) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let swimmers_parsed = individual_results.iter().map(|r| r.swimmers.len()).sum::<usize>()
+        + relay_results.iter()
+            .flat_map(|r| &r.teams)
+            .map(|t| t.swimmers.iter().filter(|s| !s.name.is_empty()).count())
+            .sum::<usize>();
+    let stats = ScrapeStats {
+        events_attempted,
+        events_succeeded: events_attempted - errors.len(),
+        events_failed: errors.len(),
+        swimmers_parsed,
+        fetch_retries: client.retries() - retries_before,
+        bytes_fetched: client.bytes_fetched() - bytes_before,
+    };
+
+    let results = ParsedResults {
+        individual_results,
+        relay_results,
+        meet_title,
+        errors,
+    };
+    Ok((results, stats))
+}
+
+// ============================================================================
+// MULTI-MEET PROCESSING
+// ============================================================================
+
+/// Maximum number of meets processed at the same time by process_meets
+const MAX_CONCURRENT_MEETS: usize = 4;
+
+/// Quick meet preview from the index and the first event page, without parsing results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeetMetadata {
+    pub title: Option<String>,
+    /// Events listed on the meet index in event-number order (empty for event URLs)
+    pub events: Vec<EventListing>,
+    /// Header of the first event page (meet name, venue, records), if it could be read
+    pub event_metadata: Option<EventMetadata>,
+}
+
+/// One event from the meet index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventListing {
+    pub number: u32,
+    pub name: String,
+    pub prelims_link: Option<String>,
+    pub finals_link: Option<String>,
+}
+
+impl MeetMetadata {
+    /// Returns the venue from the first event page's header
+    pub fn venue(&self) -> Option<&str> {
+        self.event_metadata.as_ref()?.venue.as_deref()
+    }
+
+    /// Returns the meet's first and last day, from the index title or the event header
+    pub fn dates(&self) -> Option<(NaiveDate, NaiveDate)> {
+        self.title.as_deref().and_then(parse_meet_dates)
+            .or_else(|| self.event_metadata.as_ref()?.meet_dates())
+    }
+}
+
+/// Fetches a meet's title and event list from its index, plus the header of its first
+/// event page, without fetching the other event pages (one page for event URLs)
+pub async fn fetch_meet_metadata(url: &str) -> Result<MeetMetadata, Box<dyn Error>> {
+    if validate_url(url)? == UrlType::Event {
+        let event_metadata = parse_event_metadata(&fetch_html(url).await?);
+        return Ok(MeetMetadata {
+            title: event_metadata.as_ref().and_then(|m| m.meet_name.clone()),
+            events: Vec::new(),
+            event_metadata,
+        });
+    }
+
+    let meet = parse_meet_index(url).await?;
+    let mut events: Vec<EventListing> = meet.events.values()
+        .map(|event| EventListing {
+            number: event.number,
+            name: event.name.clone(),
+            prelims_link: event.prelims_link.clone(),
+            finals_link: event.finals_link.clone(),
+        })
         .collect();
+    events.sort_by_key(|event| event.number);
+
+    let first_link = events.iter()
+        .find_map(|event| event.prelims_link.as_ref().or(event.finals_link.as_ref()));
+    let event_metadata = match first_link {
+        Some(link) => match fetch_html(link).await {
+            Ok(html) => parse_event_metadata(&html),
+            Err(e) => {
+                log::warn!("Could not fetch first event page {}: {}", link, e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    Ok(MeetMetadata { title: meet.title, events, event_metadata })
+}
+
+/// Fetches a meet's first and last day from the meet index title or, failing that, the
+/// header of its first event page
+pub async fn fetch_meet_dates(url: &str) -> Result<Option<(NaiveDate, NaiveDate)>, Box<dyn Error>> {
+    Ok(fetch_meet_metadata(url).await?.dates())
+}
+
+/// Checks if a meet falls within the options' date range. Meets whose dates can't be
+/// found are kept, with a warning.
+pub async fn meet_in_date_range(url: &str, options: &ScrapeOptions) -> Result<bool, Box<dyn Error>> {
+    if options.date_range.is_unbounded() {
+        return Ok(true);
+    }
+    match fetch_meet_dates(url).await? {
+        Some((start, end)) => Ok(options.date_range.includes(start, end)),
+        None => {
+            log::warn!("Could not find meet dates for {}; including it", url);
+            Ok(true)
+        }
+    }
+}
+
+/// Parses several meet or event URLs concurrently, returning each URL's result in input
+/// order. Meets outside `options.date_range` are skipped and left out of the results.
+pub async fn process_meets(
+    urls: &[String],
+    options: &ScrapeOptions,
+) -> Vec<(String, Result<ParsedResults, Box<dyn Error>>)> {
+    stream::iter(urls)
+        .map(|url| async move {
+            match meet_in_date_range(url, options).await {
+                Ok(true) => Some((url.clone(), parse_with_options(url, options).await)),
+                Ok(false) => {
+                    log::info!("Skipping {}: outside the date range", url);
+                    None
+                }
+                Err(e) => Some((url.clone(), Err(e))),
+            }
+        })
+        .buffered(MAX_CONCURRENT_MEETS)
+        .filter_map(|result| async move { result })
+        .collect()
+        .await
+}
+
+// ============================================================================
+// OFFLINE PROCESSING
+// ============================================================================
+
+/// Parses a saved event page, e.g. one downloaded with `wget -r`
+pub fn parse_event_file(path: &Path, session: char) -> Result<ParsedEvent, Box<dyn Error>> {
+    let html = decode_html(&fs::read(path)?, None);
+    parse_event_html(&html, session)
+}
 
-    let results = join_all(futures).await;
+/// Parses a saved meet directory containing evtindex.htm and its event pages
+pub fn parse_meet_from_dir(path: &Path) -> Result<ParsedResults, Box<dyn Error>> {
+    let index_html = decode_html(&fs::read(path.join("evtindex.htm"))?, None);
+    let base_url = Url::from_directory_path(fs::canonicalize(path)?)
+        .map_err(|_| format!("Invalid meet directory: {}", path.display()))?;
 
+    let meet = parse_meet_index_html(&index_html, &base_url);
     let mut individual_results = Vec::new();
     let mut relay_results = Vec::new();
+    let mut errors = Vec::new();
+
+    for (event_name, link, session, has_prelims) in meet_event_tasks(&meet, None, SessionFilter::All, &EventFilter::default(), None) {
+        let result = Url::parse(&link)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .ok_or_else(|| format!("Event link is not a local file: {}", link).into())
+            .and_then(|file| parse_event_file(&file, session))
+            .map(|mut event| {
+                if has_prelims {
+                    event.infer_final_types();
+                }
+                event
+            });
 
-    for (i, result) in results.into_iter().enumerate() {
-        let event_name = &event_tasks[i].0;
         match result {
             Ok(ParsedEvent::Individual(er)) => individual_results.push(er),
             Ok(ParsedEvent::Relay(rr)) => relay_results.push(rr),
-            Err(e) => {
-                eprintln!("Error processing {}: {}", event_name, e);
-            }
+            Err(e) => errors.push(EventError {
+                event_name,
+                url: link,
+                session,
+                error: e.to_string(),
+            }),
         }
     }
 
     Ok(ParsedResults {
         individual_results,
         relay_results,
-        meet_title,
+        meet_title: meet.title,
+        errors,
     })
 }
 
+/// Parses a saved meet directory or a single saved event file, detecting the
+/// event session from the filename
+pub fn parse_path(path: &Path) -> Result<ParsedResults, Box<dyn Error>> {
+    if path.is_dir() {
+        return parse_meet_from_dir(path);
+    }
+
+    let session = extract_session_from_url(&path.to_string_lossy());
+    if session == SessionType::Unknown {
+        log::warn!("Could not determine session (P/F) from file name: {}", path.display());
+    }
+    Ok(parse_event_file(path, session.as_char())?.into())
+}
+
 // ============================================================================
 // MAIN ENTRY POINT
 // ============================================================================
 
 /// Parses a meet or event URL, returning individual and relay results with meet info
 pub async fn parse(url: &str) -> Result<ParsedResults, Box<dyn Error>> {
-    match detect_url_type(url) {
-        UrlType::Meet => process_meet(url).await,
+    parse_with_options(url, &ScrapeOptions::default()).await
+}
+
+/// Parses a meet or event URL using the given scrape options, failing with
+/// ScraperError::InvalidUrl before any request if the URL can't be scraped
+pub async fn parse_with_options(url: &str, options: &ScrapeOptions) -> Result<ParsedResults, Box<dyn Error>> {
+    let url_type = validate_url(url)?;
+    shared_client().set_request_interval(options.request_interval);
+
+    match url_type {
+        UrlType::Meet => process_meet(url, options).await,
         UrlType::Event => {
-            let session = extract_session_from_url(url).ok_or_else(|| {
-                eprintln!("Error: Could not determine session (P/F) from URL: {}", url);
-                "Could not determine session (P/F) from URL"
-            })?;
-            match process_event(url, session).await? {
-                ParsedEvent::Individual(result) => {
-                    let meet_title = result.metadata.as_ref()
-                        .and_then(|m| m.meet_name.clone());
-                    Ok(ParsedResults {
-                        individual_results: vec![result],
-                        relay_results: vec![],
-                        meet_title,
-                    })
-                },
-                ParsedEvent::Relay(result) => {
-                    let meet_title = result.metadata.as_ref()
-                        .and_then(|m| m.meet_name.clone());
-                    Ok(ParsedResults {
-                        individual_results: vec![],
-                        relay_results: vec![result],
-                        meet_title,
-                    })
-                },
+            let session = extract_session_from_url(url);
+            let html = fetch_html(url).await?;
+            let continuations = fetch_continuation_pages(url, &html).await?;
+            let mut event = parse_event_pages(&html, &continuations, session.as_char())?;
+            if options.keep_raw {
+                event.keep_raw_text(&html, &continuations);
             }
+            Ok(event.into())
+        }
+    }
+}
+
+impl From<ParsedEvent> for ParsedResults {
+    /// Wraps a single parsed event, taking the meet title from its metadata
+    fn from(event: ParsedEvent) -> Self {
+        match event {
+            ParsedEvent::Individual(result) => {
+                let meet_title = result.metadata.as_ref()
+                    .and_then(|m| m.meet_name.clone());
+                ParsedResults {
+                    individual_results: vec![result],
+                    relay_results: vec![],
+                    meet_title,
+                    errors: vec![],
+                }
+            },
+            ParsedEvent::Relay(result) => {
+                let meet_title = result.metadata.as_ref()
+                    .and_then(|m| m.meet_name.clone());
+                ParsedResults {
+                    individual_results: vec![],
+                    relay_results: vec![result],
+                    meet_title,
+                    errors: vec![],
+                }
+            },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_url_type_ignores_case_and_query() {
+        for url in [
+            "https://results.example.com/meet/240327F003.htm",
+            "https://results.example.com/meet/240327F003.HTM",
+            "https://results.example.com/meet/EVT3.HTM",
+            "https://results.example.com/meet/240327P003.htm?refresh=1",
+            "https://results.example.com/meet/240327P003.Htm#top",
+            "https://results.example.com/meet/240327F003.htm/",
+        ] {
+            assert_eq!(detect_url_type(url), UrlType::Event, "{}", url);
+        }
+        for url in [
+            "https://results.example.com/meet/",
+            "https://results.example.com/meet",
+            "https://results.example.com/meet/?page=evtindex.htm",
+        ] {
+            assert_eq!(detect_url_type(url), UrlType::Meet, "{}", url);
+        }
+    }
+
+    #[test]
+    fn validate_url_checks_event_file_names_in_any_case() {
+        assert_eq!(validate_url("https://results.example.com/meet/240327F003.HTM").unwrap(), UrlType::Event);
+        assert_eq!(validate_url("https://results.example.com/meet/240327p003.htm?x=1").unwrap(), UrlType::Event);
+        assert_eq!(validate_url("https://results.example.com/meet/").unwrap(), UrlType::Meet);
+        assert!(validate_url("https://results.example.com/meet/EVT3.HTM").is_err());
+        assert!(validate_url("https://results.example.com/meet/240327F003.HTML").is_err());
+        assert!(validate_url("ftp://results.example.com/meet/").is_err());
+    }
+
+    const FIRST_PAGE: &str = "https://results.example.com/meet/240327F003.htm";
+
+    fn linked_page(next: &str) -> String {
+        format!("<html><body><pre>results</pre><a href=\"{}\">Next page</a></body></html>", next)
+    }
+
+    #[tokio::test]
+    async fn continuation_loop_stops_at_visited_page() {
+        let pages: std::collections::HashMap<String, String> = [
+            ("https://results.example.com/meet/240327F003_2.htm", linked_page("240327F003_3.htm")),
+            ("https://results.example.com/meet/240327F003_3.htm", linked_page("240327F003_2.htm")),
+        ].into_iter().map(|(url, html)| (url.to_string(), html)).collect();
+
+        let mut fetched = Vec::new();
+        let continuations = follow_continuations(FIRST_PAGE, &linked_page("240327F003_2.htm"), |link| {
+            fetched.push(link.clone());
+            let page: Result<String, Box<dyn Error>> = pages.get(&link).cloned().ok_or_else(|| format!("unexpected fetch {}", link).into());
+            async move { page }
+        }).await.unwrap();
+
+        assert_eq!(continuations.len(), 2);
+        assert_eq!(fetched.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn continuation_chain_stops_at_page_cap() {
+        let mut fetched = 0;
+        let continuations = follow_continuations(FIRST_PAGE, &linked_page("240327F003_2.htm"), |link| {
+            fetched += 1;
+            let number: usize = link.trim_end_matches(".htm").rsplit('_').next().unwrap().parse().unwrap();
+            let page = linked_page(&format!("240327F003_{}.htm", number + 1));
+            async move { Ok::<_, Box<dyn Error>>(page) }
+        }).await.unwrap();
+
+        assert_eq!(continuations.len(), MAX_EVENT_PAGES - 1);
+        assert_eq!(fetched, MAX_EVENT_PAGES - 1);
+    }
+
+    fn numbered_event(headline: &str, session: char) -> EventResults {
+        EventResults {
+            event_name: headline.to_string(),
+            session,
+            metadata: None,
+            race_info: parse_race_info(headline),
+            swimmers: Vec::new(),
+            content_hash: String::new(),
+            raw_text: None,
+            leadoff: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn event_by_number_tells_mens_and_womens_events_apart() {
+        let results = ParsedResults {
+            individual_results: vec![
+                numbered_event("Event 3  Women 200 Yard Freestyle", 'P'),
+                numbered_event("Event 3  Women 200 Yard Freestyle", 'F'),
+                numbered_event("Event 3  Men 200 Yard Freestyle", 'P'),
+                numbered_event("Event 3  Men 200 Yard Freestyle", 'F'),
+            ],
+            relay_results: Vec::new(),
+            meet_title: None,
+            errors: Vec::new(),
+        };
+        let name = |event: Option<EventRef>| match event {
+            Some(EventRef::Individual(result)) => (result.event_name.clone(), result.session),
+            other => panic!("expected an individual event, got {:?}", other),
+        };
+
+        assert_eq!(name(results.event_by_number(3, Some("Men"))), ("Event 3  Men 200 Yard Freestyle".to_string(), 'P'));
+        assert_eq!(name(results.event_by_number(3, Some("women"))), ("Event 3  Women 200 Yard Freestyle".to_string(), 'P'));
+        assert_eq!(name(results.event_by_number_session(3, Some("Men"), 'F')), ("Event 3  Men 200 Yard Freestyle".to_string(), 'F'));
+        assert_eq!(name(results.event_by_number_session(3, Some("Women"), 'F')), ("Event 3  Women 200 Yard Freestyle".to_string(), 'F'));
+        assert_eq!(name(results.event_by_number(3, None)), ("Event 3  Women 200 Yard Freestyle".to_string(), 'P'));
+        assert!(results.event_by_number(3, Some("Mixed")).is_none());
+        assert_eq!(results.events_by_number(3).count(), 4);
+    }
+
+    #[test]
+    fn only_events_with_prelims_infer_finals_from_place() {
+        let html = r#"<html><body><h2>Big Ten Championships</h2>
+<a href="250226P003.htm">3 Women 200 Yard Freestyle Prelims</a>
+<a href="250226F003.htm">3 Women 200 Yard Freestyle Finals</a>
+<a href="250226F004.htm">4 Women 1650 Yard Freestyle Finals</a>
+</body></html>"#;
+        let meet = parse_meet_index_html(html, &Url::parse("https://results.example.com/bigten/").unwrap());
+
+        let tasks = meet_event_tasks(&meet, None, SessionFilter::All, &EventFilter::default(), None);
+        let flags: Vec<(char, bool)> = tasks.iter().map(|(_, _, session, has_prelims)| (*session, *has_prelims)).collect();
+        assert_eq!(flags, vec![('P', true), ('F', true), ('F', false)]);
+    }
+
+    #[test]
+    fn parsed_results_round_trip_through_json() {
+        let mut results = output::tests::sample_results();
+        let event = &mut results.individual_results[0];
+        event.metadata = Some(EventMetadata {
+            venue: Some("Spieker Aquatics Center".to_string()),
+            meet_name: Some("Big Ten Championships".to_string()),
+            event_headline: event.event_name.clone(),
+            records: vec!["NCAA: ! 4:24.06 2017 Katie Ledecky, Stanford".to_string()],
+            entry_count: Some(24),
+        });
+        event.content_hash = hash_content("results");
+        event.raw_text = Some("  1 Smith, Jane  SR Stanford  4:40.00  4:35.10".to_string());
+        event.warnings = vec!["swimmer section skipped: too few fields: 3".to_string()];
+        let swimmer = &mut event.swimmers[0];
+        swimmer.tied = true;
+        swimmer.standards = vec!["q".to_string()];
+        swimmer.classification = Some("S9".to_string());
+        swimmer.final_type = Some(FinalType::Championship);
+        swimmer.dq_description = Some("Early take-off".to_string());
+        swimmer.prelim_splits = vec![Split::new(50, "26.30")];
+        let swimmer = &mut results.relay_results[0].teams[0].swimmers[0];
+        swimmer.gender = Some('W');
+        swimmer.leg_time = Some("22.10".to_string());
+        results.errors.push(EventError {
+            event_name: "Women 1650 Yard Freestyle".to_string(),
+            url: "https://results.example.com/meet/240327F020.htm".to_string(),
+            session: 'F',
+            error: "HTTP 404 Not Found".to_string(),
+        });
+
+        let json = serde_json::to_string(&results).unwrap();
+        let read: ParsedResults = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, results);
+    }
+
+    #[test]
+    fn kept_raw_text_includes_continuation_pages() {
+        let first = "<html><body><pre>Event 3  Women 500 Yard Freestyle
+===============================================================================
+  1 Smith, Jane          SR Stanford          4:40.00    4:35.10
+     r:+0.65  26.10        54.20 (28.10)
+</pre></body></html>";
+        let continuations = vec!["<html><body><pre>Event 3  Women 500 Yard Freestyle
+    Name                    Yr School                 Seed Time  Finals Time
+  2 Jones, Amy           JR Texas             4:41.00    4:37.00
+     r:+0.70  26.30        54.40 (28.10)
+</pre></body></html>".to_string()];
+
+        let mut event = parse_event_pages(first, &continuations, 'F').unwrap();
+        event.keep_raw_text(first, &continuations);
+        let ParsedEvent::Individual(result) = event else {
+            panic!("expected an individual event");
+        };
+        let raw_text = result.raw_text.unwrap();
+        assert!(raw_text.contains("1 Smith, Jane"));
+        assert!(raw_text.contains("2 Jones, Amy"));
+        assert!(!raw_text.contains("Seed Time"));
+        assert_eq!(hash_content(&raw_text), result.content_hash);
+    }
+}
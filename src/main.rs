@@ -1,14 +1,123 @@
+use chrono::NaiveDate;
 use clap::{Parser, ValueEnum};
+use futures::StreamExt;
+use indicatif::{ProgressBar, ProgressStyle};
 use realtime_results_scraper::{
-    parse, print_individual_results, print_relay_results,
-    write_results_to_folders, OutputOptions
+    parse_with_options, parse_path, parse_meet_index, process_meet_stream, process_meets, meet_in_date_range, detect_url_type, validate_url, print_individual_results,
+    print_relay_results, write_results_to_folders, write_combined_csv, write_athletes_csv, write_exchange_report_csv, write_individual_csv_to, write_relay_csv_to, write_results_ndjson, write_results_json, write_results_json_to, write_results_jsonl, write_markdown_report, write_html_report, data_file, write_html_report_to, meet_output_path, prepare_output_dir, resolve_output_conflict, build_headers, set_shared_client,
+    EventResults, RelayResults, FolderNaming, FolderWriter, HtmlCache, HttpClient, NameFormat, OnConflict, OutputOptions, DateRange, RelaySplitLayout, SortKey, TimeFormat, ParsedResults, RetryPolicy, ScrapeOptions, SessionFilter, EventFilter, ProgressEvent, UrlType,
+    DEFAULT_CONCURRENCY
 };
-use std::io::{self, BufRead};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug, Clone, ValueEnum)]
 enum OutputFormat {
     Csv,
     Stdout,
+    /// One JSON object per event per line
+    Ndjson,
+    /// One JSON document per meet
+    Json,
+    /// One JSON object per swimmer or relay team per line, to stdout
+    Jsonl,
+    /// Append to a SQLite database (see --db; requires the sqlite feature)
+    Sqlite,
+    /// One Excel workbook per meet (requires the xlsx feature)
+    Xlsx,
+    /// Markdown tables per event, to <MeetName>/report.md (or stdout with --stdout)
+    Markdown,
+    /// One static HTML report with sortable tables, to <MeetName>/report.html (or stdout with --stdout)
+    Html,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum NameFormatArg {
+    AsIs,
+    FirstLast,
+    LastFirst,
+}
+
+impl From<NameFormatArg> for NameFormat {
+    fn from(arg: NameFormatArg) -> Self {
+        match arg {
+            NameFormatArg::AsIs => NameFormat::AsIs,
+            NameFormatArg::FirstLast => NameFormat::FirstLast,
+            NameFormatArg::LastFirst => NameFormat::LastFirst,
+        }
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum OnConflictArg {
+    Overwrite,
+    Suffix,
+}
+
+impl From<OnConflictArg> for OnConflict {
+    fn from(arg: OnConflictArg) -> Self {
+        match arg {
+            OnConflictArg::Overwrite => OnConflict::Overwrite,
+            OnConflictArg::Suffix => OnConflict::Suffix,
+        }
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum SortArg {
+    Page,
+    Place,
+    Time,
+    Name,
+    School,
+}
+
+impl From<SortArg> for SortKey {
+    fn from(arg: SortArg) -> Self {
+        match arg {
+            SortArg::Page => SortKey::PageOrder,
+            SortArg::Place => SortKey::Place,
+            SortArg::Time => SortKey::FinalTime,
+            SortArg::Name => SortKey::Name,
+            SortArg::School => SortKey::School,
+        }
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum TimeFormatArg {
+    Raw,
+    Seconds,
+    Centiseconds,
+}
+
+impl From<TimeFormatArg> for TimeFormat {
+    fn from(arg: TimeFormatArg) -> Self {
+        match arg {
+            TimeFormatArg::Raw => TimeFormat::Raw,
+            TimeFormatArg::Seconds => TimeFormat::Seconds,
+            TimeFormatArg::Centiseconds => TimeFormat::Centiseconds,
+        }
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum SessionArg {
+    All,
+    Finals,
+    Prelims,
+}
+
+impl From<SessionArg> for SessionFilter {
+    fn from(arg: SessionArg) -> Self {
+        match arg {
+            SessionArg::All => SessionFilter::All,
+            SessionArg::Finals => SessionFilter::FinalsOnly,
+            SessionArg::Prelims => SessionFilter::PrelimsOnly,
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -16,13 +125,26 @@ enum OutputFormat {
 #[command(about = "Parse swimming meet results from URLs")]
 #[command(next_line_help = true)]
 struct Args {
-    /// Realtime-results meet or event URL to parse
-    url: Option<String>,
+    /// Realtime-results meet or event URL(s) to parse, or saved meet directories/event files
+    urls: Vec<String>,
 
     /// Output format
     #[arg(short, long, value_enum, default_value = "csv")]
     output: OutputFormat,
 
+    /// SQLite database file for --output sqlite (appended to if it exists)
+    #[arg(long, default_value = "results.db")]
+    db: PathBuf,
+
+    /// Print JSON output to stdout instead of writing it to the meet folder
+    #[arg(long, default_value = "false")]
+    json_stdout: bool,
+
+    /// Print CSV, JSON, markdown, or HTML output to stdout instead of writing files; CSV relay
+    /// rows follow the individual rows after a blank line
+    #[arg(long, default_value = "false")]
+    stdout: bool,
+
     /// Disable metadata output
     #[arg(long, default_value = "false")]
     no_metadata: bool,
@@ -30,57 +152,672 @@ struct Args {
     /// Number of swimmers to include per event [default: all]
     #[arg(short, long)]
     top: Option<u32>,
+
+    /// Only fetch the first N events of each meet, by event number [default: all]
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// With --top, also keep DQ/NS entries that have no place
+    #[arg(long, default_value = "false")]
+    include_unplaced: bool,
+
+    /// Only output DQ/NS entries that have no place (a DQ report)
+    #[arg(long, default_value = "false")]
+    only_unplaced: bool,
+
+    /// How swimmer names are written
+    #[arg(long, value_enum, default_value = "as-is")]
+    name_format: NameFormatArg,
+
+    /// Omit datetime ids and name event folders by event number and session
+    /// (e.g. E03_F_Women_500_Yard_Freestyle/results.csv), so repeated runs write the same paths
+    #[arg(long, default_value = "false")]
+    deterministic: bool,
+
+    /// With --deterministic, whether an existing meet folder is overwritten or a "-1" suffix added
+    #[arg(long, value_enum, default_value = "overwrite")]
+    on_conflict: OnConflictArg,
+
+    /// Append rows to existing results CSVs instead of replacing them (use with --deterministic)
+    #[arg(long, default_value = "false")]
+    append: bool,
+
+    /// Group relay split columns by swimmer (swimmer1_split1, ...) instead of split1..splitN
+    #[arg(long, default_value = "false")]
+    relay_splits_by_swimmer: bool,
+
+    /// Order of rows within each event (default: as on the results page)
+    #[arg(long, value_enum, default_value = "page")]
+    sort: SortArg,
+
+    /// Only output swimmers and relays whose school/team contains this text (repeatable)
+    #[arg(long = "team")]
+    teams: Vec<String>,
+
+    /// Truncate school and team names longer than this many characters in markdown reports
+    #[arg(long)]
+    max_school_len: Option<usize>,
+
+    /// Leave split columns out of results output
+    #[arg(long, default_value = "false")]
+    no_splits: bool,
+
+    /// Leave reaction time columns out of results output
+    #[arg(long, default_value = "false")]
+    no_reaction: bool,
+
+    /// Add seed_seconds and per-split _seconds columns (decimal seconds) to results output
+    #[arg(long, default_value = "false")]
+    numeric_times: bool,
+
+    /// Add relay leadoff swims as individual events (marked in the leadoff column)
+    #[arg(long, default_value = "false")]
+    leadoffs: bool,
+
+    /// How seed, final, and split times are written in CSV and JSON output
+    #[arg(long, value_enum, default_value = "raw")]
+    time_format: TimeFormatArg,
+
+    /// Write tab-separated .tsv files instead of .csv
+    #[arg(long, default_value = "false", conflicts_with = "delimiter")]
+    tsv: bool,
+
+    /// Field delimiter for CSV output (a single ASCII character, e.g. ';')
+    #[arg(long)]
+    delimiter: Option<char>,
+
+    /// Also write one-row-per-split CSVs (splits_*.csv, relay_splits_*.csv) in event folders
+    #[arg(long, default_value = "false")]
+    splits_long: bool,
+
+    /// Directory to create meet folders and files in (default: current directory)
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+
+    /// Write all_results.csv and all_relays.csv for the whole meet instead of event folders
+    #[arg(long, default_value = "false")]
+    combined: bool,
+
+    /// Also write athletes.csv with every swim grouped by athlete (name and school)
+    #[arg(long, default_value = "false")]
+    by_athlete: bool,
+
+    /// Also write exchanges.csv listing negative and close (under 0.10s) relay exchanges
+    #[arg(long, default_value = "false")]
+    exchange_report: bool,
+
+    /// Only write requested data to stdout (no progress messages)
+    #[arg(short, long, default_value = "false")]
+    quiet: bool,
+
+    /// Which session of each event to fetch
+    #[arg(long, value_enum, default_value = "all")]
+    session: SessionArg,
+
+    /// Only fetch these event numbers (e.g. "1,5-9,21")
+    #[arg(long)]
+    events: Option<String>,
+
+    /// Only fetch events whose stroke contains this text (e.g. "breast")
+    #[arg(long)]
+    stroke: Option<String>,
+
+    /// Only fetch events for this gender (e.g. "women")
+    #[arg(long)]
+    gender: Option<String>,
+
+    /// Skip meets that ended before this date (YYYY-MM-DD)
+    #[arg(long)]
+    since: Option<NaiveDate>,
+
+    /// Skip meets that started after this date (YYYY-MM-DD)
+    #[arg(long)]
+    until: Option<NaiveDate>,
+
+    /// Write each event as soon as it is parsed instead of after the whole meet
+    #[arg(long, default_value = "false")]
+    stream: bool,
+
+    /// Keep each event's raw results text in JSON output (raw_text), for debugging the parser
+    #[arg(long, default_value = "false")]
+    keep_raw: bool,
+
+    /// List the events and URLs that would be fetched, without fetching results
+    #[arg(long, default_value = "false")]
+    dry_run: bool,
+
+    /// Maximum number of event pages fetched at the same time
+    #[arg(long, default_value_t = DEFAULT_CONCURRENCY)]
+    concurrency: usize,
+
+    /// Minimum seconds between requests, shared across concurrent fetches
+    #[arg(long, default_value_t = 0.0, value_parser = parse_crawl_delay)]
+    crawl_delay: f64,
+
+    /// Check each host's robots.txt and honor its Disallow and Crawl-delay rules
+    #[arg(long, default_value = "false")]
+    respect_robots: bool,
+
+    /// Cookie header sent with every request (for password-protected results)
+    #[arg(long)]
+    cookie: Option<String>,
+
+    /// Extra request header as "Name: Value" (repeatable)
+    #[arg(long = "header")]
+    headers: Vec<String>,
+
+    /// Directory for caching fetched pages between runs
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+
+    /// Re-download pages even if they are cached
+    #[arg(long, default_value = "false")]
+    refresh: bool,
+
+    /// Re-download cached pages older than this many seconds
+    #[arg(long)]
+    cache_max_age: Option<u64>,
+
+    /// Exit with a non-zero status if any event failed to fetch or parse
+    #[arg(long, default_value = "false")]
+    fail_on_error: bool,
+}
+
+/// Parses a non-negative number of seconds
+fn parse_crawl_delay(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs >= 0.0 => Ok(secs),
+        _ => Err(format!("invalid delay in seconds: {}", value)),
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
+    // Progress messages are logged at info level; --quiet keeps only warnings and errors
+    let default_level = if args.quiet { "warn" } else { "info" };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_level))
+        .format_timestamp(None)
+        .format_target(false)
+        .init();
 
-    // Get URL from args or stdin
-    let url = match args.url {
-        Some(url) => url,
-        None => {
-            println!("Enter meet or event URL:");
-            let stdin = io::stdin();
-            stdin.lock().lines().next()
-                .ok_or("No input provided")??
-        }
+    // Get URLs from args or stdin
+    let urls: Vec<String> = if args.urls.is_empty() {
+        println!("Enter meet or event URL:");
+        let stdin = io::stdin();
+        let line = stdin.lock().lines().next()
+            .ok_or("No input provided")??;
+        vec![line.trim().to_string()]
+    } else {
+        args.urls.iter().map(|u| u.trim().to_string()).collect()
     };
 
-    let url = url.trim();
-    println!("Parsing: {}\n", url);
+    // Configure the shared client before any page is fetched
+    let headers = build_headers(args.cookie.as_deref(), &args.headers)?;
+    let mut client = HttpClient::with_headers(RetryPolicy::default(), headers)?;
+    if let Some(ref dir) = args.cache_dir {
+        client.set_cache(HtmlCache {
+            dir: dir.clone(),
+            refresh: args.refresh,
+            max_age: args.cache_max_age.map(Duration::from_secs),
+        });
+    }
+    client.set_respect_robots(args.respect_robots);
+    let _ = set_shared_client(client);
 
-    // Enter parse flow
-    let results = parse(url).await?;
+    let event_filter = EventFilter {
+        numbers: match args.events {
+            Some(ref spec) => EventFilter::parse_numbers(spec)?,
+            None => Vec::new(),
+        },
+        stroke: args.stroke.clone(),
+        gender: args.gender.clone(),
+    };
+
+    let mut scrape_options = ScrapeOptions {
+        concurrency: args.concurrency,
+        request_interval: Duration::from_secs_f64(args.crawl_delay),
+        session_filter: args.session.into(),
+        event_filter,
+        keep_raw: args.keep_raw,
+        date_range: DateRange { since: args.since, until: args.until },
+        limit: args.limit,
+        ..ScrapeOptions::default()
+    };
+
+    let progress_bar = if args.quiet { ProgressBar::hidden() } else { new_progress_bar() };
+    let bar = progress_bar.clone();
+    scrape_options.on_progress(move |event| match event {
+        ProgressEvent::Started { total } => bar.inc_length(total as u64),
+        ProgressEvent::EventFetched { name, session } => bar.set_message(format!("{} ({})", name, session)),
+        ProgressEvent::EventParsed { .. }
+        | ProgressEvent::EventUnchanged { .. }
+        | ProgressEvent::EventFailed { .. } => bar.inc(1),
+    });
+
+    if args.dry_run {
+        for url in &urls {
+            dry_run(url, &scrape_options).await?;
+        }
+        return Ok(());
+    }
 
     // Build options from args (None = all participants, Some(n) = top n placements)
     let options = OutputOptions {
         metadata: !args.no_metadata,
         top_n: args.top,
+        include_unplaced: args.include_unplaced,
+        only_unplaced: args.only_unplaced,
+        name_format: args.name_format.into(),
+        naming: if args.deterministic { FolderNaming::Deterministic } else { FolderNaming::Unique },
+        combined: args.combined,
+        output_dir: args.output_dir.clone(),
+        on_conflict: args.on_conflict.into(),
+        append: args.append,
+        splits_long: args.splits_long,
+        include_splits: !args.no_splits,
+        include_reaction: !args.no_reaction,
+        max_school_len: args.max_school_len,
+        team_filter: if args.teams.is_empty() { None } else { Some(args.teams.clone()) },
+        sort_by: args.sort.into(),
+        relay_split_layout: if args.relay_splits_by_swimmer { RelaySplitLayout::PerSwimmer } else { RelaySplitLayout::Flat },
+        numeric_times: args.numeric_times,
+        by_athlete: args.by_athlete,
+        exchange_report: args.exchange_report,
+        include_leadoffs: args.leadoffs,
+        time_format: args.time_format.into(),
+        delimiter: match (args.tsv, args.delimiter) {
+            (true, _) => b'\t',
+            (false, Some(c)) if c.is_ascii() => c as u8,
+            (false, Some(c)) => return Err(format!("Delimiter must be a single ASCII character: {}", c).into()),
+            (false, None) => b',',
+        },
     };
+    prepare_output_dir(&options)?;
+
+    let to_stdout = args.stdout || args.json_stdout;
+    let mut failures: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+
+    if args.stream {
+        for url in &urls {
+            if !Path::new(url).exists() && !meet_in_date_range(url, &scrape_options).await? {
+                log::info!("Skipping {}: outside the date range", url);
+                continue;
+            }
+            if let Err(e) = stream_results(url, &scrape_options, &args.output, &options, to_stdout, &args.db, &mut failures, &mut warnings).await {
+                failures.push(format!("{}: {}", url, e));
+            }
+        }
+    } else if let [url] = urls.as_slice() {
+        log::info!("Parsing: {}", url);
+        if !Path::new(url).exists() && !meet_in_date_range(url, &scrape_options).await? {
+            log::info!("Skipping {}: outside the date range", url);
+            progress_bar.finish_and_clear();
+            return Ok(());
+        }
+
+        // Enter parse flow (local paths are parsed offline)
+        let results = if Path::new(url).exists() {
+            parse_path(Path::new(url))?
+        } else {
+            parse_with_options(url, &scrape_options).await?
+        };
+        output_results(&results, Some(url.as_str()), &args.output, &options, to_stdout, &args.db)?;
+        failures.extend(results.errors.iter().map(|e| e.to_string()));
+        warnings.extend(results.warnings());
+    } else {
+        log::info!("Parsing {} URLs", urls.len());
+
+        let (paths, urls): (Vec<String>, Vec<String>) = urls.into_iter()
+            .partition(|u| Path::new(u).exists());
+        let path_results = paths.iter()
+            .map(|path| (path.clone(), parse_path(Path::new(path))));
+        let url_results = process_meets(&urls, &scrape_options).await;
+
+        for (url, result) in path_results.chain(url_results) {
+            match result {
+                Ok(results) => {
+                    output_results(&results, Some(url.as_str()), &args.output, &options, to_stdout, &args.db)?;
+                    failures.extend(results.errors.iter().map(|e| e.to_string()));
+                    warnings.extend(results.warnings());
+                }
+                Err(e) => failures.push(format!("{}: {}", url, e)),
+            }
+        }
+    }
+
+    progress_bar.finish_and_clear();
+
+    if !warnings.is_empty() {
+        eprintln!("\n{} parse warning(s):", warnings.len());
+        for warning in &warnings {
+            eprintln!("  {}", warning);
+        }
+    }
+
+    if !failures.is_empty() {
+        eprintln!("\n{} failure(s):", failures.len());
+        for failure in &failures {
+            eprintln!("  {}", failure);
+        }
+        if args.fail_on_error {
+            std::process::exit(1);
+        }
+    }
 
-    match args.output {
+    Ok(())
+}
+
+/// Creates the progress bar shown while event pages are fetched
+fn new_progress_bar() -> ProgressBar {
+    let bar = ProgressBar::new(0);
+    if let Ok(style) = ProgressStyle::with_template("{bar:40} {pos}/{len} events {wide_msg}") {
+        bar.set_style(style);
+    }
+    bar
+}
+
+/// Writes or prints one meet's results in the requested format
+fn output_results(
+    results: &ParsedResults,
+    source_url: Option<&str>,
+    output: &OutputFormat,
+    options: &OutputOptions,
+    to_stdout: bool,
+    db: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // JSON output is serialized from the results themselves, so its times are rewritten
+    // here; CSV rows format times as they are written
+    let json_output = matches!(output, OutputFormat::Json | OutputFormat::Jsonl | OutputFormat::Ndjson);
+    let reformat_times = json_output && options.time_format != TimeFormat::Raw;
+    let adjusted;
+    let results = if options.include_leadoffs || reformat_times {
+        let mut copy = results.clone();
+        if options.include_leadoffs {
+            copy.include_leadoffs();
+        }
+        if reformat_times {
+            copy.format_times(options.time_format);
+        }
+        adjusted = copy;
+        &adjusted
+    } else {
+        results
+    };
+
+    match output {
+        OutputFormat::Csv if to_stdout => write_csv_stdout(results, options)?,
+        OutputFormat::Csv if options.combined => {
+            let meet_path = write_combined_csv(
+                &results.individual_results,
+                &results.relay_results,
+                results.meet_title.as_deref(),
+                options,
+            )?;
+            if options.by_athlete {
+                write_athletes_csv(results, options, &meet_path.join(data_file("athletes", options)))?;
+            }
+            if options.exchange_report {
+                write_exchange_report_csv(results, options, &meet_path.join(data_file("exchanges", options)))?;
+            }
+        }
         OutputFormat::Csv => {
-            write_results_to_folders(
+            let manifest = write_results_to_folders(
                 &results.individual_results,
                 &results.relay_results,
                 results.meet_title.as_deref(),
-                &options,
+                source_url,
+                options,
             )?;
+            if options.by_athlete {
+                write_athletes_csv(results, options, &manifest.meet_path.join(data_file("athletes", options)))?;
+            }
+            if options.exchange_report {
+                write_exchange_report_csv(results, options, &manifest.meet_path.join(data_file("exchanges", options)))?;
+            }
         }
         OutputFormat::Stdout => {
             for event_results in &results.individual_results {
-                print_individual_results(event_results, &options);
+                print_individual_results(event_results, options);
             }
             for relay_event in &results.relay_results {
-                print_relay_results(relay_event, &options);
+                print_relay_results(relay_event, options);
             }
         }
+        OutputFormat::Jsonl => {
+            write_results_jsonl(results, options, &mut io::stdout().lock())?;
+        }
+        OutputFormat::Sqlite => write_sqlite(results, db)?,
+        OutputFormat::Xlsx => {
+            let path = resolve_output_conflict(
+                meet_output_path(results.meet_title.as_deref(), options).with_extension("xlsx"),
+                options,
+            );
+            write_xlsx(results, options, &path)?;
+        }
+        OutputFormat::Json if to_stdout => {
+            write_results_json_to(results, options, &mut io::stdout().lock())?;
+        }
+        OutputFormat::Json => {
+            let meet_path = resolve_output_conflict(meet_output_path(results.meet_title.as_deref(), options), options);
+            fs::create_dir_all(&meet_path)?;
+            write_results_json(results, options, &meet_path.join("results.json"))?;
+        }
+        OutputFormat::Markdown if to_stdout => {
+            write_markdown_report(results, options, &mut io::stdout().lock())?;
+        }
+        OutputFormat::Markdown => {
+            let meet_path = resolve_output_conflict(meet_output_path(results.meet_title.as_deref(), options), options);
+            fs::create_dir_all(&meet_path)?;
+            let path = meet_path.join("report.md");
+            write_markdown_report(results, options, &mut BufWriter::new(File::create(&path)?))?;
+            log::info!("Report written to {}", path.display());
+        }
+        OutputFormat::Html if to_stdout => {
+            write_html_report_to(results, options, &mut io::stdout().lock())?;
+        }
+        OutputFormat::Html => {
+            let meet_path = resolve_output_conflict(meet_output_path(results.meet_title.as_deref(), options), options);
+            fs::create_dir_all(&meet_path)?;
+            write_html_report(results, options, &meet_path.join("report.html"))?;
+        }
+        OutputFormat::Ndjson => {
+            let path = ndjson_path(results.meet_title.as_deref(), options);
+            let mut file = BufWriter::new(File::create(&path)?);
+            write_results_ndjson(&results.individual_results, &results.relay_results, &mut file)?;
+            log::info!("Results written to {}", path.display());
+        }
     }
 
     let total = results.individual_results.len() + results.relay_results.len();
-    println!("\nParsed {} event(s) ({} individual, {} relay)",
+    log::info!("Parsed {} event(s) ({} individual, {} relay)",
              total, results.individual_results.len(), results.relay_results.len());
 
     Ok(())
 }
+
+/// Prints the individual results CSV, then a blank line and the relay results CSV if any
+fn write_csv_stdout(results: &ParsedResults, options: &OutputOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let individual: Vec<&EventResults> = results.individual_results.iter().collect();
+    let relay: Vec<&RelayResults> = results.relay_results.iter().collect();
+    let mut stdout = io::stdout().lock();
+
+    if !individual.is_empty() {
+        write_individual_csv_to(&individual, options, &mut stdout)?;
+    }
+    if !relay.is_empty() {
+        if !individual.is_empty() {
+            writeln!(stdout)?;
+        }
+        write_relay_csv_to(&relay, options, &mut stdout)?;
+    }
+    Ok(())
+}
+
+/// Appends results to the SQLite database at `db`
+#[cfg(feature = "sqlite")]
+fn write_sqlite(results: &ParsedResults, db: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    realtime_results_scraper::write_results_sqlite(results, db)
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn write_sqlite(_results: &ParsedResults, _db: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    Err("SQLite output requires building with --features sqlite".into())
+}
+
+/// Writes results to an Excel workbook at `path`
+#[cfg(feature = "xlsx")]
+fn write_xlsx(results: &ParsedResults, options: &OutputOptions, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    realtime_results_scraper::write_results_xlsx(results, options, path)
+}
+
+#[cfg(not(feature = "xlsx"))]
+fn write_xlsx(_results: &ParsedResults, _options: &OutputOptions, _path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    Err("XLSX output requires building with --features xlsx".into())
+}
+
+/// Returns the NDJSON output path for a meet, e.g. "Big_Ten_Championships_20250327_101500.ndjson"
+fn ndjson_path(meet_title: Option<&str>, options: &OutputOptions) -> PathBuf {
+    resolve_output_conflict(meet_output_path(meet_title, options).with_extension("ndjson"), options)
+}
+
+/// Writes or prints each event of a meet as soon as it is parsed, recording failed events
+/// and parse warnings
+#[allow(clippy::too_many_arguments)]
+async fn stream_results(
+    url: &str,
+    scrape_options: &ScrapeOptions,
+    output: &OutputFormat,
+    options: &OutputOptions,
+    to_stdout: bool,
+    db: &Path,
+    failures: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Event pages and saved files have nothing to stream; JSON, SQLite, XLSX, reports,
+    // combined CSV, athlete CSV, and the exchange report write one meet at a time
+    let per_meet_output = matches!(output, OutputFormat::Json | OutputFormat::Sqlite | OutputFormat::Xlsx | OutputFormat::Markdown | OutputFormat::Html)
+        || (matches!(output, OutputFormat::Csv) && (options.combined || options.by_athlete || options.exchange_report || to_stdout));
+    if Path::new(url).exists() || detect_url_type(url) == UrlType::Event || per_meet_output {
+        let results = if Path::new(url).exists() {
+            parse_path(Path::new(url))?
+        } else {
+            parse_with_options(url, scrape_options).await?
+        };
+        warnings.extend(results.warnings());
+        return output_results(&results, Some(url), output, options, to_stdout, db);
+    }
+
+    log::info!("Streaming: {}", url);
+    let mut events = Box::pin(process_meet_stream(url, scrape_options).await?);
+    let mut writer: Option<FolderWriter> = None;
+    let mut ndjson: Option<BufWriter<File>> = None;
+    let mut parsed = 0;
+
+    while let Some(result) = events.next().await {
+        let mut results = match result {
+            Ok(event) => ParsedResults::from(event),
+            Err(e) => {
+                failures.push(e.to_string());
+                continue;
+            }
+        };
+        parsed += 1;
+        warnings.extend(results.warnings());
+        if options.include_leadoffs {
+            results.include_leadoffs();
+        }
+        if matches!(output, OutputFormat::Jsonl | OutputFormat::Ndjson) {
+            results.format_times(options.time_format);
+        }
+
+        match output {
+            OutputFormat::Csv => {
+                // The meet folder is named after the first event's meet name
+                if writer.is_none() {
+                    let mut folder_writer = FolderWriter::new(results.meet_title.as_deref(), options)?;
+                    folder_writer.set_source_url(url);
+                    writer = Some(folder_writer);
+                }
+                if let Some(ref mut writer) = writer {
+                    writer.write(&results.individual_results, &results.relay_results)?;
+                }
+            }
+            OutputFormat::Stdout => {
+                for event_results in &results.individual_results {
+                    print_individual_results(event_results, options);
+                }
+                for relay_event in &results.relay_results {
+                    print_relay_results(relay_event, options);
+                }
+            }
+            OutputFormat::Jsonl => {
+                write_results_jsonl(&results, options, &mut io::stdout().lock())?;
+            }
+            OutputFormat::Json | OutputFormat::Sqlite | OutputFormat::Xlsx | OutputFormat::Markdown | OutputFormat::Html => unreachable!("per-meet output is not streamed"),
+            OutputFormat::Ndjson => {
+                if ndjson.is_none() {
+                    let path = ndjson_path(results.meet_title.as_deref(), options);
+                    ndjson = Some(BufWriter::new(File::create(&path)?));
+                    log::info!("Writing results to {}", path.display());
+                }
+                if let Some(ref mut file) = ndjson {
+                    write_results_ndjson(&results.individual_results, &results.relay_results, file)?;
+                }
+            }
+        }
+    }
+
+    if let Some(writer) = writer {
+        writer.finish()?;
+    }
+    log::info!("Parsed {} event(s)", parsed);
+    Ok(())
+}
+
+/// Prints the events and URLs that would be fetched for a URL
+async fn dry_run(url: &str, scrape_options: &ScrapeOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let filter = scrape_options.session_filter;
+    if validate_url(url)? == UrlType::Event {
+        println!("Would fetch event: {}", url);
+        return Ok(());
+    }
+
+    let meet = parse_meet_index(url).await?;
+    if let Some(ref title) = meet.title {
+        println!("Meet: {}", title);
+    }
+
+    let mut events: Vec<_> = meet.events.values()
+        .filter(|e| scrape_options.event_filter.matches(e))
+        .collect();
+    events.sort_by_key(|e| e.number);
+
+    let mut pages = 0;
+    let mut listed = 0;
+    for event in &events {
+        let prelims = event.prelims_link.as_ref().filter(|_| filter.includes('P'));
+        let finals = event.finals_link.as_ref().filter(|_| filter.includes('F'));
+        if prelims.is_none() && finals.is_none() {
+            continue;
+        }
+        if scrape_options.limit.is_some_and(|limit| listed >= limit) {
+            break;
+        }
+        listed += 1;
+
+        println!("Event {}: {}", event.number, event.name);
+        if let Some(link) = prelims {
+            println!("    Prelims: {}", link);
+            pages += 1;
+        }
+        if let Some(link) = finals {
+            println!("    Finals:  {}", link);
+            pages += 1;
+        }
+    }
+
+    println!("\n{} page(s) would be fetched", pages);
+    Ok(())
+}
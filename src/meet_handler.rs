@@ -1,34 +1,62 @@
 use scraper::{Html, Selector, ElementRef};
 use std::collections::HashMap;
 use std::error::Error;
+use url::Url;
 
-use crate::utils::fetch_html;
+use crate::metadata::{is_gender, parse_race_info, RaceInfo};
+use crate::utils::{event_code_from_url, fetch_html};
 
 // ============================================================================
 // DATA STRUCTURES
 // ============================================================================
 
-/// Meet containing all events, base URL, and meet title
+/// Meet containing all events, base URL, meet title, and sessions
 pub struct Meet {
+    /// Events keyed by event_key (name, with the gender when the name lacks it)
     pub events: HashMap<String, Event>,
     pub base_url: String,
     pub title: Option<String>,
+    pub sessions: Vec<Session>,
 }
 
 /// Event with links to prelims and finals pages
 pub struct Event {
     pub name: String,
     pub number: u32,
+    /// Gender from the index link text, when given
+    pub gender: Option<String>,
     pub prelims_link: Option<String>,
     pub finals_link: Option<String>,
 }
 
+/// Session from a session index page, listing events in session order
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub name: String,
+    pub day: Option<String>,
+    pub events: Vec<u32>,
+    /// Event number, gender, and session (P/F) of each event page linked from this session
+    pub links: Vec<(u32, Option<String>, char)>,
+}
+
 /// Parsed event link from index page
 struct EventLink {
     href: String,
     event_name: String,
     event_num: u32,
     session: char,
+    gender: Option<String>,
+}
+
+/// Key for an index event: its name, prefixed with the gender when the name doesn't
+/// already include it, so same-named men's and women's events stay separate
+fn event_key(name: &str, gender: Option<&str>) -> String {
+    match gender {
+        Some(gender) if !name.split_whitespace().any(|t| t.eq_ignore_ascii_case(gender)) => {
+            format!("{} {}", gender, name)
+        }
+        _ => name.to_string(),
+    }
 }
 
 impl Meet {
@@ -38,6 +66,7 @@ impl Meet {
             events: HashMap::new(),
             base_url,
             title: None,
+            sessions: Vec::new(),
         }
     }
 
@@ -46,12 +75,25 @@ impl Meet {
         self.title = Some(title);
     }
 
+    /// Sets the meet sessions
+    pub fn set_sessions(&mut self, sessions: Vec<Session>) {
+        self.sessions = sessions;
+    }
+
+    /// Returns the session whose name matches (case-insensitive substring)
+    pub fn find_session(&self, name: &str) -> Option<&Session> {
+        let name = name.to_lowercase();
+        self.sessions.iter()
+            .find(|s| s.name.to_lowercase() == name)
+            .or_else(|| self.sessions.iter().find(|s| s.name.to_lowercase().contains(&name)))
+    }
+
     /// Adds an event to the meet
     pub fn add_event(&mut self, name: String, event: Event) {
         self.events.insert(name, event);
     }
 
-    /// Returns a mutable reference to an event by name
+    /// Returns a mutable reference to an event by key (see event_key)
     pub fn get_event_mut(&mut self, name: &str) -> Option<&mut Event> {
         self.events.get_mut(name)
     }
@@ -63,11 +105,17 @@ impl Event {
         Event {
             name,
             number,
+            gender: None,
             prelims_link: None,
             finals_link: None,
         }
     }
 
+    /// Parses gender, distance, and stroke from the index event name
+    pub fn race_info(&self) -> Option<RaceInfo> {
+        parse_race_info(&format!("Event {} {}", self.number, self.name))
+    }
+
     /// Sets the prelims or finals link based on session
     pub fn set_link(&mut self, link: String, session: char) {
         match session {
@@ -78,36 +126,67 @@ impl Event {
     }
 }
 
+impl Session {
+    /// Creates an empty session with the given name and day
+    pub fn new(name: String, day: Option<String>) -> Session {
+        Session {
+            name,
+            day,
+            events: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    /// Adds an event link, ignoring repeats of the same event or link
+    pub fn add_event(&mut self, number: u32, gender: Option<&str>, session: char) {
+        if !self.events.contains(&number) {
+            self.events.push(number);
+        }
+        if !self.contains(number, gender, session) {
+            self.links.push((number, gender.map(str::to_string), session));
+        }
+    }
+
+    /// Checks if this session includes the given event number, gender, and session (P/F).
+    /// A link without a gender matches either gender
+    pub fn contains(&self, number: u32, gender: Option<&str>, session: char) -> bool {
+        self.links.iter().any(|(n, g, s)| {
+            *n == number && *s == session && match (g.as_deref(), gender) {
+                (Some(linked), Some(gender)) => linked.eq_ignore_ascii_case(gender),
+                _ => true,
+            }
+        })
+    }
+}
+
 impl EventLink {
     /// Extracts event info from an index page link element
     fn from_element(link: ElementRef) -> Option<Self> {
         let href = link.value().attr("href")?.to_string();
         let text = link.text().next()?.to_string();
 
-        if !href.ends_with(".htm") {
-            return None;
-        }
-
-        let code = href.trim_end_matches(".htm");
-        if code.len() < 4 {
+        let path = href.split(['?', '#']).next().unwrap_or(&href).to_ascii_lowercase();
+        if !path.ends_with(".htm") && !path.ends_with(".html") {
             return None;
         }
 
-        let session = code.chars().nth(code.len() - 4)?;
+        let (session, event_num) = event_code_from_url(&href)?;
         if session != 'P' && session != 'F' {
             return None;
         }
 
-        let event_num = code[code.len() - 3..].parse().unwrap_or(0);
-
-        let event_name = text
-            .split_once(' ')
-            .map(|(_, rest)| rest.trim())
-            .unwrap_or(&text)
-            .replace(" Prelims", "")
-            .replace(" Finals", "");
+        // Drop a leading event number ("#3", "3.", "3"), keeping a leading gender
+        let event_name = match text.trim().split_once(' ') {
+            Some((first, rest)) if first.trim_start_matches('#').trim_end_matches('.').parse::<u32>().is_ok() => rest.trim(),
+            _ => text.trim(),
+        }
+        .replace(" Prelims", "")
+        .replace(" Finals", "");
+        let gender = event_name.split_whitespace()
+            .find(|t| is_gender(t))
+            .map(str::to_string);
 
-        Some(EventLink { href, event_name, event_num, session })
+        Some(EventLink { href, event_name, event_num, session, gender })
     }
 }
 
@@ -159,32 +238,235 @@ fn extract_meet_title(html: &str) -> Option<String> {
 /// Fetches and parses a meet index page, returning a Meet with all event links
 pub async fn parse_meet_index(url: &str) -> Result<Meet, Box<dyn Error>> {
     let url = url.trim_end_matches('/');
-    let mut meet = Meet::new(url.to_string());
 
     let index_url = format!("{}/evtindex.htm", url);
     let html = fetch_html(&index_url).await?;
 
+    // Trailing slash so relative hrefs resolve inside the meet folder
+    let base_url = Url::parse(&format!("{}/", url))?;
+
+    Ok(parse_meet_index_html(&html, &base_url))
+}
+
+/// Parses meet index HTML, resolving event links against the meet base URL
+/// (which may be a `file://` URL for saved meets)
+pub fn parse_meet_index_html(html: &str, base_url: &Url) -> Meet {
+    let mut meet = Meet::new(base_url.as_str().trim_end_matches('/').to_string());
+
     // Extract meet title
-    if let Some(title) = extract_meet_title(&html) {
+    if let Some(title) = extract_meet_title(html) {
         meet.set_title(title);
     }
 
-    let document = Html::parse_document(&html);
+    let document = Html::parse_document(html);
     let selector = Selector::parse("a").unwrap();
 
     for link in document.select(&selector) {
         if let Some(event_link) = EventLink::from_element(link) {
-            let full_url = format!("{}/{}", url, event_link.href);
+            let full_url = match base_url.join(&event_link.href) {
+                Ok(resolved) => resolved.to_string(),
+                Err(_) => continue,
+            };
 
-            if let Some(event) = meet.get_event_mut(&event_link.event_name) {
+            let key = event_key(&event_link.event_name, event_link.gender.as_deref());
+            if let Some(event) = meet.get_event_mut(&key) {
                 event.set_link(full_url, event_link.session);
             } else {
-                let mut event = Event::new(event_link.event_name.clone(), event_link.event_num);
+                let mut event = Event::new(event_link.event_name, event_link.event_num);
+                event.gender = event_link.gender;
                 event.set_link(full_url, event_link.session);
-                meet.add_event(event_link.event_name, event);
+                meet.add_event(key, event);
             }
         }
     }
 
-    Ok(meet)
+    meet
+}
+
+// ============================================================================
+// SESSION INDEX PARSING
+// ============================================================================
+
+const WEEKDAYS: &[&str] = &[
+    "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday",
+    "Mon", "Tue", "Tues", "Wed", "Thu", "Thur", "Thurs", "Fri", "Sat", "Sun",
+];
+
+/// Max number of sessN.htm pages probed when discovering meet sessions
+const MAX_SESSION_PAGES: u32 = 30;
+
+/// Parses a session heading line (e.g. "Session 1 - Thursday Prelims")
+fn parse_session_heading(line: &str) -> Option<Session> {
+    let first = line.split_whitespace().next()?;
+    if !first.eq_ignore_ascii_case("session") {
+        return None;
+    }
+
+    let day = line
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .find(|token| WEEKDAYS.iter().any(|d| d.eq_ignore_ascii_case(token)))
+        .map(|d| d.to_string());
+
+    Some(Session::new(line.to_string(), day))
+}
+
+/// Extracts sessions and their event links from session index HTML
+pub fn parse_session_html(html: &str) -> Vec<Session> {
+    let document = Html::parse_document(html);
+    let mut sessions: Vec<Session> = Vec::new();
+
+    for node in document.root_element().descendants() {
+        if let Some(element) = ElementRef::wrap(node) {
+            if element.value().name() != "a" {
+                continue;
+            }
+            // Diving and other non-standard links simply fail to parse and are skipped
+            if let Some(event_link) = EventLink::from_element(element) {
+                if sessions.is_empty() {
+                    sessions.push(Session::new("Session".to_string(), None));
+                }
+                if let Some(current) = sessions.last_mut() {
+                    current.add_event(event_link.event_num, event_link.gender.as_deref(), event_link.session);
+                }
+            }
+            continue;
+        }
+
+        if let Some(text) = node.value().as_text() {
+            let inside_link = node.ancestors()
+                .any(|a| a.value().as_element().is_some_and(|e| e.name() == "a"));
+            if inside_link {
+                continue;
+            }
+            for line in text.lines() {
+                if let Some(session) = parse_session_heading(line.trim()) {
+                    sessions.push(session);
+                }
+            }
+        }
+    }
+
+    sessions
+}
+
+/// Fetches and parses a session index page (e.g. sess1.htm)
+pub async fn parse_session_index(url: &str) -> Result<Vec<Session>, Box<dyn Error>> {
+    let html = fetch_html(url).await?;
+    Ok(parse_session_html(&html))
+}
+
+/// Discovers all session pages (sess1.htm, sess2.htm, ...) for a meet
+pub async fn parse_meet_sessions(url: &str) -> Result<Vec<Session>, Box<dyn Error>> {
+    let url = url.trim_end_matches('/');
+    let mut sessions = Vec::new();
+
+    for n in 1..=MAX_SESSION_PAGES {
+        let session_url = format!("{}/sess{}.htm", url, n);
+        let page_sessions = match parse_session_index(&session_url).await {
+            Ok(s) => s,
+            Err(_) => break,
+        };
+        if page_sessions.iter().all(|s| s.events.is_empty()) {
+            break;
+        }
+        sessions.extend(page_sessions);
+    }
+
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_numbered_mens_and_womens_events_stay_separate() {
+        let html = r#"<html><body><h2>Big Ten Championships</h2>
+<a href="250226P003.htm">3 Women 200 Yard Freestyle Prelims</a>
+<a href="250226F003.htm">3 Women 200 Yard Freestyle Finals</a>
+<a href="250227P003.htm">3 Men 200 Yard Freestyle Prelims</a>
+<a href="250227F003.htm">3 Men 200 Yard Freestyle Finals</a>
+</body></html>"#;
+        let base_url = Url::parse("https://results.example.com/bigten/").unwrap();
+        let meet = parse_meet_index_html(html, &base_url);
+
+        assert_eq!(meet.events.len(), 2);
+        let women = &meet.events["Women 200 Yard Freestyle"];
+        let men = &meet.events["Men 200 Yard Freestyle"];
+        assert_eq!((women.number, women.gender.as_deref()), (3, Some("Women")));
+        assert_eq!((men.number, men.gender.as_deref()), (3, Some("Men")));
+        assert_eq!(women.prelims_link.as_deref(), Some("https://results.example.com/bigten/250226P003.htm"));
+        assert_eq!(women.finals_link.as_deref(), Some("https://results.example.com/bigten/250226F003.htm"));
+        assert_eq!(men.prelims_link.as_deref(), Some("https://results.example.com/bigten/250227P003.htm"));
+        assert_eq!(men.finals_link.as_deref(), Some("https://results.example.com/bigten/250227F003.htm"));
+    }
+
+    #[test]
+    fn relative_root_relative_and_absolute_hrefs_resolve() {
+        let html = r#"<html><body><h2>NCAA Championships</h2>
+<a href="240327F003.htm">3 Women 200 Yard Freestyle Finals</a>
+<a href="/meet/240327F004.htm">4 Women 100 Yard Butterfly Finals</a>
+<a href="https://mirror.example.org/live/240327F005.htm">5 Women 100 Yard Backstroke Finals</a>
+</body></html>"#;
+        let base_url = Url::parse("https://results.example.com/2024/ncaa/").unwrap();
+        let meet = parse_meet_index_html(html, &base_url);
+
+        let link = |name: &str| meet.events[name].finals_link.as_deref();
+        assert_eq!(meet.events.len(), 3);
+        assert_eq!(link("Women 200 Yard Freestyle"), Some("https://results.example.com/2024/ncaa/240327F003.htm"));
+        assert_eq!(link("Women 100 Yard Butterfly"), Some("https://results.example.com/meet/240327F004.htm"));
+        assert_eq!(link("Women 100 Yard Backstroke"), Some("https://mirror.example.org/live/240327F005.htm"));
+        assert_eq!(meet.events["Women 100 Yard Butterfly"].number, 4);
+    }
+
+    #[test]
+    fn event_links_in_any_case_or_with_a_query_are_read() {
+        let html = r#"<html><body><h2>NCAA Championships</h2>
+<a href="240327F003.HTM">3 Women 200 Yard Freestyle Finals</a>
+<a href="240327p004.htm?refresh=30">4 Women 100 Yard Butterfly Prelims</a>
+<a href="240327F004.Htm#top">4 Women 100 Yard Butterfly Finals</a>
+<a href="sess1.htm">Session 1</a>
+<a href="240327S005.htm">5 Women 100 Yard Backstroke Swim-off</a>
+</body></html>"#;
+        let base_url = Url::parse("https://results.example.com/ncaa/").unwrap();
+        let meet = parse_meet_index_html(html, &base_url);
+
+        assert_eq!(meet.events.len(), 2);
+        let free = &meet.events["Women 200 Yard Freestyle"];
+        assert_eq!(free.number, 3);
+        assert_eq!(free.finals_link.as_deref(), Some("https://results.example.com/ncaa/240327F003.HTM"));
+        let fly = &meet.events["Women 100 Yard Butterfly"];
+        assert_eq!(fly.number, 4);
+        assert_eq!(fly.prelims_link.as_deref(), Some("https://results.example.com/ncaa/240327p004.htm?refresh=30"));
+        assert_eq!(fly.finals_link.as_deref(), Some("https://results.example.com/ncaa/240327F004.Htm#top"));
+    }
+
+    #[test]
+    fn session_links_keep_mens_and_womens_events_apart() {
+        let html = r#"<html><body><pre>Session 1 - Thursday Prelims
+<a href="250226P003.htm">3 Women 200 Yard Freestyle Prelims</a>
+Session 2 - Thursday Finals
+<a href="250226F003.htm">3 Women 200 Yard Freestyle Finals</a>
+<a href="250226F004.htm">4 Men 200 Yard Freestyle Finals</a>
+Session 3 - Friday Prelims
+<a href="250227P003.htm">3 Men 200 Yard Freestyle Prelims</a>
+</pre></body></html>"#;
+        let sessions = parse_session_html(html);
+
+        assert_eq!(sessions.len(), 3);
+        assert!(sessions[0].contains(3, Some("Women"), 'P'));
+        assert!(!sessions[0].contains(3, Some("Men"), 'P'));
+        assert!(sessions[1].contains(3, Some("women"), 'F'));
+        assert!(!sessions[1].contains(3, Some("Men"), 'F'));
+        assert!(sessions[2].contains(3, Some("Men"), 'P'));
+        assert!(!sessions[2].contains(3, Some("Women"), 'P'));
+        assert!(sessions[2].contains(3, None, 'P'));
+    }
+
+    #[test]
+    fn event_key_adds_gender_missing_from_name() {
+        assert_eq!(event_key("200 Free", Some("Men")), "Men 200 Free");
+        assert_eq!(event_key("Women 200 Free", Some("Women")), "Women 200 Free");
+        assert_eq!(event_key("200 Free", None), "200 Free");
+    }
 }
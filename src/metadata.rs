@@ -1,20 +1,27 @@
+use chrono::NaiveDate;
 use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+use crate::time::SwimTime;
+use crate::utils::is_valid_time_format;
 
 // ============================================================================
 // DATA STRUCTURES
 // ============================================================================
 
 /// Metadata extracted from event page header
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EventMetadata {
     pub venue: Option<String>,
     pub meet_name: Option<String>,
     pub event_headline: String,
     pub records: Vec<String>,
+    /// Number of entries stated in the page header (e.g. "48 entries"), if any
+    pub entry_count: Option<u32>,
 }
 
 /// Race type information parsed from event headline
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RaceInfo {
     pub event_number: u32,
     pub gender: Option<String>,
@@ -25,6 +32,23 @@ pub struct RaceInfo {
     pub other: Vec<String>,
 }
 
+impl EventMetadata {
+    /// Returns the meet's first and last day, read from the meet name or venue line
+    /// (e.g. "Big Ten Championships - 2/26/2025 to 3/1/2025")
+    pub fn meet_dates(&self) -> Option<(NaiveDate, NaiveDate)> {
+        [&self.meet_name, &self.venue].into_iter()
+            .flatten()
+            .find_map(|line| parse_meet_dates(line))
+    }
+
+    /// Returns the times listed in the record lines (the first time on each line)
+    pub fn record_times(&self) -> Vec<SwimTime> {
+        self.records.iter()
+            .filter_map(|line| line.split_whitespace().find_map(|token| token.parse().ok()))
+            .collect()
+    }
+}
+
 impl RaceInfo {
     /// Returns course code (SCY, SCM, LCM) based on course string
     pub fn course_code(&self) -> Option<&'static str> {
@@ -43,6 +67,36 @@ impl RaceInfo {
     }
 }
 
+// ============================================================================
+// MEET DATES
+// ============================================================================
+
+/// Parses a date written as M/D/YYYY, M/D/YY, or YYYY-MM-DD
+fn parse_date(token: &str) -> Option<NaiveDate> {
+    let token = token.trim_matches(|c: char| !c.is_ascii_digit());
+    if token.contains('-') {
+        return NaiveDate::parse_from_str(token, "%Y-%m-%d").ok();
+    }
+
+    let fields: Vec<&str> = token.split('/').collect();
+    let [month, day, year] = fields.as_slice() else {
+        return None;
+    };
+    let year: i32 = match year.len() {
+        2 => 2000 + year.parse::<i32>().ok()?,
+        4 => year.parse().ok()?,
+        _ => return None,
+    };
+    NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+}
+
+/// Finds the first and last dates in a line such as "2/26/2025 to 3/1/2025"; a single
+/// date gives a one-day range
+pub fn parse_meet_dates(text: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let dates: Vec<NaiveDate> = text.split_whitespace().filter_map(parse_date).collect();
+    Some((*dates.first()?, *dates.last()?))
+}
+
 // ============================================================================
 // KNOWN VALUES FOR TOKEN CLASSIFICATION
 // ============================================================================
@@ -62,14 +116,11 @@ const STROKES: &[&str] = &[
 // PARSING - RACE INFO
 // ============================================================================
 
-/// Parses race information from event headline using token classification
+/// Parses race information from event headline ("Event 3 ...", "#3 ...", or "3. ...")
+/// using token classification
 pub fn parse_race_info(headline: &str) -> Option<RaceInfo> {
     let tokens: Vec<&str> = headline.split_whitespace().collect();
-
-    let event_idx = tokens.iter().position(|&t| t.eq_ignore_ascii_case("Event"))?;
-    let event_number: u32 = tokens.get(event_idx + 1)?.parse().ok()?;
-
-    let remaining = &tokens[event_idx + 2..];
+    let (event_number, remaining) = split_event_number(&tokens)?;
 
     let mut gender: Option<String> = None;
     let mut distance: Option<u16> = None;
@@ -116,7 +167,26 @@ pub fn parse_race_info(headline: &str) -> Option<RaceInfo> {
     })
 }
 
-fn is_gender(token: &str) -> bool {
+/// Finds the event number ("Event 3", or a leading "#3" or "3.") and returns it with the
+/// tokens that follow it
+fn split_event_number<'a>(tokens: &'a [&'a str]) -> Option<(u32, &'a [&'a str])> {
+    if let Some(event_idx) = tokens.iter().position(|&t| t.eq_ignore_ascii_case("Event")) {
+        let event_number = tokens.get(event_idx + 1)?.parse().ok()?;
+        return Some((event_number, &tokens[event_idx + 2..]));
+    }
+
+    let first = tokens.first()?;
+    let number = first.strip_prefix('#').or_else(|| first.strip_suffix('.'))?;
+    Some((number.parse().ok()?, &tokens[1..]))
+}
+
+/// Checks if a line is an event headline, in any of the forms parse_race_info accepts
+fn is_event_headline(line: &str) -> bool {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    split_event_number(&tokens).is_some()
+}
+
+pub(crate) fn is_gender(token: &str) -> bool {
     GENDERS.iter().any(|&g| g.eq_ignore_ascii_case(token))
 }
 
@@ -132,6 +202,34 @@ fn is_stroke_word(token: &str) -> bool {
     STROKES.iter().any(|&s| s.eq_ignore_ascii_case(token))
 }
 
+/// Extracts an entry count from lines like "48 entries", "Entries: 48", or "Splash count: 48"
+fn parse_entry_count(line: &str) -> Option<u32> {
+    let lower = line.to_lowercase();
+    let tokens: Vec<&str> = lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    tokens.windows(2).find_map(|pair| match pair {
+        [count, "entries" | "entrants"] => count.parse().ok(),
+        ["entries" | "entrants" | "count", count] => count.parse().ok(),
+        _ => None,
+    })
+}
+
+/// Checks if a line ends the page header: the results column titles ("Name  Yr School ...")
+/// or a result row (a place or "--" followed by a time)
+fn ends_header(line: &str) -> bool {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    match tokens.as_slice() {
+        ["Name" | "Team", ..] => true,
+        [first, rest @ ..] => {
+            (first.parse::<u32>().is_ok() || *first == "--") && rest.iter().any(|t| is_valid_time_format(t))
+        }
+        [] => false,
+    }
+}
+
 /// Checks if a line is a delimiter line (e.g., "=================")
 fn is_delimiter_line(line: &str) -> bool {
     line.chars().all(|c| c == '=') && line.len() >= 5
@@ -156,6 +254,10 @@ pub fn parse_event_metadata(html: &str) -> Option<EventMetadata> {
     let mut found_event = false;
 
     let mut in_records_section = false;
+    let entry_count = lines.iter()
+        .map(|line| line.trim())
+        .take_while(|line| !ends_header(line))
+        .find_map(parse_entry_count);
 
     for line in &lines {
         let trimmed = line.trim();
@@ -164,7 +266,7 @@ pub fn parse_event_metadata(html: &str) -> Option<EventMetadata> {
             continue;
         }
 
-        if trimmed.contains("Event") && trimmed.chars().any(|c| c.is_ascii_digit()) {
+        if !found_event && is_event_headline(trimmed) {
             event_headline = trimmed.to_string();
             found_event = true;
             continue;
@@ -223,6 +325,71 @@ pub fn parse_event_metadata(html: &str) -> Option<EventMetadata> {
         meet_name,
         event_headline,
         records,
+        entry_count,
     })
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(body: &str) -> String {
+        format!("<html><body><pre>{}</pre></body></html>", body)
+    }
+
+    fn headline_page(headline: &str) -> String {
+        page(&format!("\
+Licensed to Big Ten Conference - Site License HY-TEK's MEET MANAGER 8.0
+2025 Big Ten Championships - 2/26/2025 to 3/1/2025
+Results - McCorkle Aquatic Pavilion
+
+{}
+===============================================================================
+    Meet: M 1:30.12  3/1/2025   Jane Smith, Stanford
+===============================================================================
+    Name                    Yr School                 Seed Time  Finals Time
+===============================================================================
+  1 Smith, Jane             SR Stanford             1:58.00    1:56.10
+", headline))
+    }
+
+    #[test]
+    fn event_metadata_found_for_each_headline_form() {
+        for (headline, gender, distance) in [
+            ("Event 3  Women 200 Yard Freestyle", "Women", 200),
+            ("#3 Men 200 Free", "Men", 200),
+            ("3. Women 100 Back", "Women", 100),
+        ] {
+            let metadata = parse_event_metadata(&headline_page(headline)).unwrap();
+            assert_eq!(metadata.event_headline, headline);
+            assert_eq!(metadata.meet_name.as_deref(), Some("2025 Big Ten Championships - 2/26/2025 to 3/1/2025"));
+            assert_eq!(metadata.records.len(), 1);
+
+            let race_info = parse_race_info(&metadata.event_headline).unwrap();
+            assert_eq!(race_info.event_number, 3);
+            assert_eq!(race_info.gender.as_deref(), Some(gender));
+            assert_eq!(race_info.distance, Some(distance));
+        }
+    }
+
+    #[test]
+    fn entry_count_read_from_header_only() {
+        let html = page("\
+2025 Big Ten Championships - 2/26/2025 to 3/1/2025
+Event 3  Women 200 Yard Freestyle
+48 entries
+===============================================================================
+    Meet: M 1:30.12  3/1/2025   Jane Smith, Stanford
+===============================================================================
+    Name                    Yr School                 Seed Time  Finals Time
+===============================================================================
+  1 Smith, Jane             SR Stanford             1:58.00    1:56.10
+     Protest upheld: 12 entries rescored
+");
+        assert_eq!(parse_event_metadata(&html).unwrap().entry_count, Some(48));
+
+        let decoy_only = html.replace("48 entries\n", "");
+        assert_eq!(parse_event_metadata(&decoy_only).unwrap().entry_count, None);
+    }
+}
@@ -1,168 +1,860 @@
-use crate::event_handler::EventResults;
-use crate::relay_handler::RelayResults;
-use crate::utils::{generate_unique_id, sanitize_name};
-use std::collections::HashMap;
+use crate::event_handler::{EventResults, Split, Swimmer};
+use crate::metadata::{EventMetadata, RaceInfo};
+use crate::relay_handler::{RelayResults, RelayTeam, CLOSE_EXCHANGE_LIMIT, EARLY_TAKEOFF_LIMIT, RELAY_LEGS};
+use crate::{EventRef, ParsedResults};
+use crate::time::SwimTime;
+use crate::utils::{generate_unique_id, parse_time_to_centiseconds, sanitize_name, session_label, name_first_last, name_last_first};
+use std::collections::{BTreeMap, HashSet};
 use std::error::Error;
+use serde::Serialize;
 use std::fs::{self, File};
-use std::path::PathBuf;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// Delimited output file stems; the extension follows the delimiter (see data_file)
+const CSV_OUTPUT_FILE: &str = "results";
+const RELAY_CSV_OUTPUT_FILE: &str = "relay_results";
+const METADATA_CSV_OUTPUT_FILE: &str = "metadata";
+const COMBINED_CSV_FILE: &str = "all_results";
+const COMBINED_RELAY_CSV_FILE: &str = "all_relays";
+const MEET_SUMMARY_CSV_FILE: &str = "meet_summary";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// CSV writer builder using the options' delimiter
+pub(crate) fn csv_writer(options: &OutputOptions) -> csv::WriterBuilder {
+    let mut builder = csv::WriterBuilder::new();
+    builder.delimiter(options.delimiter);
+    builder
+}
+
+/// File name for delimited output: ".tsv" when tab-delimited, otherwise ".csv"
+pub fn data_file(stem: &str, options: &OutputOptions) -> String {
+    let extension = if options.delimiter == b'\t' { "tsv" } else { "csv" };
+    format!("{}.{}", stem, extension)
+}
+
+/// Formats a parsed time as decimal seconds for CSV output (empty if unparsed)
+fn seconds_column(time: Option<SwimTime>) -> String {
+    time.map(|t| format!("{:.2}", t.as_seconds())).unwrap_or_default()
+}
 
-const CSV_OUTPUT_FILE: &str = "results.csv";
-const RELAY_CSV_OUTPUT_FILE: &str = "relay_results.csv";
-const METADATA_CSV_OUTPUT_FILE: &str = "metadata.csv";
+/// Formats a time string as decimal seconds (empty for DQ, NT, and other non-times)
+fn time_seconds(time: &str) -> String {
+    seconds_column(time.parse().ok())
+}
+
+/// Split column names, followed by a `_seconds` column for each when numeric_times is set
+fn split_header_columns(names: Vec<String>, options: &OutputOptions) -> Vec<String> {
+    let seconds: Vec<String> = if options.numeric_times {
+        names.iter().map(|name| format!("{}_seconds", name)).collect()
+    } else {
+        Vec::new()
+    };
+    names.into_iter().chain(seconds).collect()
+}
+
+/// Split time values, followed by each in seconds when numeric_times is set
+fn split_value_columns(times: Vec<String>, options: &OutputOptions) -> Vec<String> {
+    let seconds: Vec<String> = if options.numeric_times {
+        times.iter().map(|time| time_seconds(time)).collect()
+    } else {
+        Vec::new()
+    };
+    times.iter().map(|time| options.time_format.apply(time)).chain(seconds).collect()
+}
+
+// ============================================================================
+// RESULT ROWS
+// ============================================================================
+
+/// Event context columns shared by individual and relay rows
+fn event_columns(event_name: &str, session: char, race_info: Option<&RaceInfo>) -> Vec<String> {
+    let (event_number, gender, distance, course, stroke) = if let Some(info) = race_info {
+        (
+            info.event_number,
+            info.gender.clone().unwrap_or_default(),
+            info.distance.unwrap_or(0),
+            info.course.clone().unwrap_or_default(),
+            info.stroke.clone().unwrap_or_default(),
+        )
+    } else {
+        (0, String::new(), 0, String::new(), String::new())
+    };
+
+    vec![
+        event_name.to_string(),
+        session_label(session).to_string(),
+        event_number.to_string(),
+        gender,
+        distance.to_string(),
+        course,
+        stroke,
+    ]
+}
+
+/// Pads split times out to `max_splits` columns
+fn split_columns(splits: &[Split], max_splits: usize) -> impl Iterator<Item = String> + '_ {
+    (0..max_splits).map(|i| splits.get(i).map(|s| s.time.clone()).unwrap_or_default())
+}
+
+/// Most splits recorded by any swimmer in the events (0 when splits are excluded)
+pub(crate) fn max_individual_splits(results: &[&EventResults], options: &OutputOptions) -> usize {
+    if !options.include_splits {
+        return 0;
+    }
+    results.iter()
+        .flat_map(|e| e.swimmers.iter())
+        .map(|s| s.splits.len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Most splits recorded by any relay team in the events (0 when splits are excluded)
+pub(crate) fn max_relay_splits(results: &[&RelayResults], options: &OutputOptions) -> usize {
+    if !options.include_splits {
+        return 0;
+    }
+    results.iter()
+        .flat_map(|e| e.teams.iter())
+        .map(|t| t.splits.len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Column names for individual results rows
+pub(crate) fn individual_header(options: &OutputOptions, max_splits: usize) -> Vec<String> {
+    let mut header: Vec<&str> = vec![
+        "event_name", "session", "event_number", "gender", "distance",
+        "course", "stroke", "place", "name", "year", "school", "seed_time", "final_time", "final_seconds",
+    ];
+    if options.numeric_times {
+        header.push("seed_seconds");
+    }
+    if options.include_reaction {
+        header.push("reaction_time");
+    }
+    header.extend(["dq_description", "final_type", "leadoff"]);
+
+    let mut header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+    header.extend(split_header_columns((1..=max_splits).map(|i| format!("split{}", i)).collect(), options));
+    header
+}
+
+/// One individual results row, matching individual_header
+pub(crate) fn individual_row(
+    event: &EventResults,
+    swimmer: &Swimmer,
+    options: &OutputOptions,
+    max_splits: usize,
+) -> Vec<String> {
+    let mut row = event_columns(&event.event_name, event.session, event.race_info.as_ref());
+    row.extend([
+        swimmer.place.map(|p| p.to_string()).unwrap_or_default(),
+        options.name_format.apply(&swimmer.name),
+        swimmer.year.clone(),
+        swimmer.school.clone(),
+        options.time_format.apply(swimmer.seed_time.as_deref().unwrap_or("")),
+        options.time_format.apply(&swimmer.final_time),
+        seconds_column(swimmer.final_time_parsed()),
+    ]);
+    if options.numeric_times {
+        row.push(time_seconds(swimmer.seed_time.as_deref().unwrap_or("")));
+    }
+    if options.include_reaction {
+        row.push(swimmer.reaction_time.clone().unwrap_or_default());
+    }
+    row.extend([
+        swimmer.dq_description.clone().unwrap_or_default(),
+        swimmer.final_type.map(|t| t.to_string()).unwrap_or_default(),
+        event.leadoff.to_string(),
+    ]);
+    row.extend(split_value_columns(split_columns(&swimmer.splits, max_splits).collect(), options));
+    row
+}
+
+/// Column names for relay results rows
+pub(crate) fn relay_header(options: &OutputOptions, max_splits: usize) -> Vec<String> {
+    let mut header: Vec<&str> = vec![
+        "event_name", "session", "event_number", "gender", "distance", "course", "stroke",
+        "place", "team_name", "designation", "seed_time", "final_time", "final_seconds",
+    ];
+    if options.numeric_times {
+        header.push("seed_seconds");
+    }
+    header.extend([
+        "dq_description", "swimmer1_name", "swimmer1_year", "swimmer2_name", "swimmer2_year",
+        "swimmer3_name", "swimmer3_year", "swimmer4_name", "swimmer4_year",
+        "swimmer1_leg", "swimmer2_leg", "swimmer3_leg", "swimmer4_leg",
+        "swimmer1_gender", "swimmer2_gender", "swimmer3_gender", "swimmer4_gender", "alternate_names",
+    ]);
+    if options.include_reaction {
+        header.extend(["swimmer1_reaction", "swimmer2_reaction", "swimmer3_reaction", "swimmer4_reaction"]);
+    }
+
+    let mut header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+    let splits: Vec<String> = match options.relay_split_layout {
+        RelaySplitLayout::Flat => (1..=max_splits).map(|i| format!("split{}", i)).collect(),
+        RelaySplitLayout::PerSwimmer => {
+            let per_leg = splits_per_leg(max_splits);
+            (1..=RELAY_LEGS)
+                .flat_map(|leg| (1..=per_leg).map(move |i| format!("swimmer{}_split{}", leg, i)))
+                .collect()
+        }
+    };
+    header.extend(split_header_columns(splits, options));
+    header
+}
+
+/// Split columns per swimmer when `max_splits` are spread over the legs
+fn splits_per_leg(max_splits: usize) -> usize {
+    max_splits.div_ceil(RELAY_LEGS)
+}
+
+/// Split columns grouped by leg: each split goes to the leg its distance falls in, padded
+/// to `max_splits / 4` columns per swimmer
+fn per_swimmer_split_columns(event: &RelayResults, team: &RelayTeam, max_splits: usize) -> Vec<String> {
+    let per_leg = splits_per_leg(max_splits);
+    let mut legs: Vec<Vec<String>> = vec![Vec::new(); RELAY_LEGS];
+
+    let distance = event.race_info.as_ref().and_then(|i| i.distance)
+        .or_else(|| team.splits.last().map(|s| s.distance))
+        .unwrap_or(0);
+    let leg_distance = (distance / RELAY_LEGS as u16).max(1);
+
+    for split in &team.splits {
+        let leg = (usize::from(split.distance.saturating_sub(1) / leg_distance)).min(RELAY_LEGS - 1);
+        legs[leg].push(split.time.clone());
+    }
+
+    legs.into_iter()
+        .flat_map(|mut leg| {
+            leg.resize(per_leg, String::new());
+            leg
+        })
+        .collect()
+}
+
+/// One relay results row, matching relay_header
+pub(crate) fn relay_row(
+    event: &RelayResults,
+    team: &RelayTeam,
+    options: &OutputOptions,
+    max_splits: usize,
+) -> Vec<String> {
+    let mut row = event_columns(&event.event_name, event.session, event.race_info.as_ref());
+    row.extend([
+        team.place.map(|p| p.to_string()).unwrap_or_default(),
+        team.team_name.clone(),
+        team.relay_designation.map(String::from).unwrap_or_default(),
+        options.time_format.apply(team.seed_time.as_deref().unwrap_or("")),
+        options.time_format.apply(&team.final_time),
+        seconds_column(team.final_time_parsed()),
+    ]);
+    if options.numeric_times {
+        row.push(time_seconds(team.seed_time.as_deref().unwrap_or("")));
+    }
+    row.push(team.dq_description.clone().unwrap_or_default());
+
+    for i in 0..4 {
+        match team.swimmers.get(i) {
+            Some(swimmer) => {
+                row.push(options.name_format.apply(&swimmer.name));
+                row.push(swimmer.year.clone());
+            }
+            None => {
+                row.push(String::new());
+                row.push(String::new());
+            }
+        }
+    }
+
+    for i in 0..4 {
+        row.push(team.swimmers.get(i)
+            .and_then(|s| s.leg_time.as_deref())
+            .map(|time| options.time_format.apply(time))
+            .unwrap_or_default());
+    }
+
+    for i in 0..4 {
+        row.push(team.swimmers.get(i)
+            .and_then(|s| s.gender)
+            .map(String::from)
+            .unwrap_or_default());
+    }
+
+    let alternates: Vec<String> = team.alternates().iter()
+        .filter(|s| !s.name.is_empty())
+        .map(|s| options.name_format.apply(&s.name))
+        .collect();
+    row.push(alternates.join("; "));
+
+    if options.include_reaction {
+        for i in 0..4 {
+            row.push(team.swimmers.get(i)
+                .and_then(|s| s.reaction_time.clone())
+                .unwrap_or_default());
+        }
+    }
+
+    let splits: Vec<String> = match options.relay_split_layout {
+        RelaySplitLayout::Flat => split_columns(&team.splits, max_splits).collect(),
+        RelaySplitLayout::PerSwimmer => per_swimmer_split_columns(event, team, max_splits),
+    };
+    row.extend(split_value_columns(splits, options));
+    row
+}
+
+/// Opens a results CSV for writing. In append mode an existing file keeps its header and
+/// split columns: rows with more splits than the header are cut to fit, with a warning.
+/// Returns the writer and the number of split columns rows should have.
+fn open_results_csv(
+    path: &Path,
+    options: &OutputOptions,
+    header: fn(&OutputOptions, usize) -> Vec<String>,
+    max_splits: usize,
+) -> Result<(csv::Writer<File>, usize), Box<dyn Error>> {
+    let has_content = fs::metadata(path).map(|m| m.len() > 0).unwrap_or(false);
+    if options.append && has_content {
+        let existing = csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .from_path(path)?
+            .headers()?
+            .clone();
+        let existing_splits = existing.iter()
+            .filter(|h| (h.starts_with("split") || h.contains("_split")) && !h.ends_with("_seconds"))
+            .count();
+        if max_splits > existing_splits {
+            log::warn!(
+                "{} has {} split columns; dropping later splits from appended rows",
+                path.display(), existing_splits
+            );
+        }
+
+        let file = fs::OpenOptions::new().append(true).open(path)?;
+        return Ok((csv_writer(options).from_writer(file), existing_splits));
+    }
+
+    let mut writer = csv_writer(options).from_writer(File::create(path)?);
+    writer.write_record(header(options, max_splits))?;
+    Ok((writer, max_splits))
+}
+
+// ============================================================================
+// LONG-FORMAT SPLITS CSV OUTPUT
+// ============================================================================
+
+/// Rows of (split_index, distance, cumulative_time, interval_time) for a split list
+fn split_rows(splits: &[Split], time_format: TimeFormat) -> Vec<[String; 4]> {
+    let mut previous: Option<SwimTime> = None;
+    splits.iter().enumerate().map(|(i, split)| {
+        let cumulative: Option<SwimTime> = split.time.parse().ok();
+        let interval = match (cumulative, previous) {
+            (Some(current), Some(prev)) => current.checked_sub(prev),
+            (current, None) => current,
+            (None, Some(_)) => None,
+        };
+        previous = cumulative;
+        [
+            (i + 1).to_string(),
+            split.distance.to_string(),
+            time_format.apply(&split.time),
+            interval.map(|t| time_format.apply(&t.to_string())).unwrap_or_default(),
+        ]
+    }).collect()
+}
+
+/// Writes one row per individual split, as an alternative to the wide split columns
+pub fn write_splits_csv(
+    results: &[&EventResults],
+    options: &OutputOptions,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv_writer(options).from_path(path)?;
+    writer.write_record([
+        "event_number", "session", "swimmer_name", "school",
+        "split_index", "distance", "cumulative_time", "interval_time",
+    ])?;
+
+    for event in results {
+        let event_number = event.race_info.as_ref().map(|i| i.event_number.to_string()).unwrap_or_default();
+        for swimmer in event.swimmers.iter().filter(|s| include_swimmer(s, options)) {
+            for split in split_rows(&swimmer.splits, options.time_format) {
+                let mut row = vec![
+                    event_number.clone(),
+                    session_label(event.session).to_string(),
+                    options.name_format.apply(&swimmer.name),
+                    swimmer.school.clone(),
+                ];
+                row.extend(split);
+                writer.write_record(row)?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes one row per relay split, keyed by team
+pub fn write_relay_splits_csv(
+    results: &[&RelayResults],
+    options: &OutputOptions,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv_writer(options).from_path(path)?;
+    writer.write_record([
+        "event_number", "session", "team_name",
+        "split_index", "distance", "cumulative_time", "interval_time",
+    ])?;
+
+    for event in results {
+        let event_number = event.race_info.as_ref().map(|i| i.event_number.to_string()).unwrap_or_default();
+        for team in event.teams.iter().filter(|t| include_team(t, options)) {
+            for split in split_rows(&team.splits, options.time_format) {
+                let mut row = vec![
+                    event_number.clone(),
+                    session_label(event.session).to_string(),
+                    team.team_name.clone(),
+                ];
+                row.extend(split);
+                writer.write_record(row)?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
 
 // ============================================================================
 // METADATA CSV OUTPUT
 // ============================================================================
 
-/// Writes event metadata to metadata.csv
+/// Column names for metadata rows
+pub(crate) const METADATA_HEADER: [&str; 6] = ["event_name", "session", "venue", "meet_name", "records", "entry_count"];
+
+/// One metadata.csv row for an event
+pub(crate) fn metadata_row(event_name: &str, session: char, metadata: Option<&EventMetadata>) -> [String; 6] {
+    let (venue, meet_name, records, entry_count) = if let Some(meta) = metadata {
+        (
+            meta.venue.clone().unwrap_or_default(),
+            meta.meet_name.clone().unwrap_or_default(),
+            meta.records.iter()
+                .map(|r| r.trim_matches('=').trim())
+                .collect::<Vec<_>>()
+                .join(" | "),
+            meta.entry_count.map(|n| n.to_string()).unwrap_or_default(),
+        )
+    } else {
+        (String::new(), String::new(), String::new(), String::new())
+    };
+
+    [event_name.to_string(), session_label(session).to_string(), venue, meet_name, records, entry_count]
+}
+
+/// Writes event metadata as CSV to any writer
+pub fn write_metadata_csv_to<W: Write>(
+    individual_results: &[&EventResults],
+    relay_results: &[&RelayResults],
+    options: &OutputOptions,
+    out: W,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv_writer(options).from_writer(out);
+    writer.write_record(METADATA_HEADER)?;
+
+    for event in individual_results {
+        writer.write_record(metadata_row(&event.event_name, event.session, event.metadata.as_ref()))?;
+    }
+    for event in relay_results {
+        writer.write_record(metadata_row(&event.event_name, event.session, event.metadata.as_ref()))?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes event metadata to metadata.csv (metadata.tsv when tab-delimited)
 pub fn write_metadata_csv(
     individual_results: &[EventResults],
     relay_results: &[RelayResults],
+    options: &OutputOptions,
 ) -> Result<(), Box<dyn Error>> {
-    let file = File::create(METADATA_CSV_OUTPUT_FILE)?;
-    let mut writer = csv::Writer::from_writer(file);
+    let individual: Vec<&EventResults> = individual_results.iter().collect();
+    let relay: Vec<&RelayResults> = relay_results.iter().collect();
+    let path = data_file(METADATA_CSV_OUTPUT_FILE, options);
+    write_metadata_csv_to(&individual, &relay, options, File::create(&path)?)?;
+    log::info!("Metadata written to {}", path);
+    Ok(())
+}
 
-    writer.write_record(["event_name", "session", "venue", "meet_name", "records"])?;
+// ============================================================================
+// JSON OUTPUT
+// ============================================================================
 
-    for event in individual_results {
-        let session = if event.session == 'P' { "Prelims" } else { "Finals" };
-        let (venue, meet_name, records) = if let Some(ref meta) = event.metadata {
-            (
-                meta.venue.clone().unwrap_or_default(),
-                meta.meet_name.clone().unwrap_or_default(),
-                meta.records.iter()
-                    .map(|r| r.trim_matches('=').trim())
-                    .collect::<Vec<_>>()
-                    .join(" | "),
-            )
-        } else {
-            (String::new(), String::new(), String::new())
-        };
+/// Checks if a placement passes the top_n filter; no-place (DQ/NS) entries pass only
+/// with include_unplaced, and are the only entries that pass with only_unplaced
+pub(crate) fn within_top_n(place: Option<u8>, options: &OutputOptions) -> bool {
+    if options.only_unplaced {
+        return place.is_none();
+    }
+    match (options.top_n, place) {
+        (None, _) => true,
+        (Some(top_n), Some(p)) => u32::from(p) <= top_n,
+        (Some(_), None) => options.include_unplaced,
+    }
+}
 
-        writer.write_record([
-            &event.event_name,
-            session,
-            &venue,
-            &meet_name,
-            &records,
-        ])?;
+/// Checks if a team or school name matches the team filter (case-insensitive substring)
+pub(crate) fn matches_team(name: &str, options: &OutputOptions) -> bool {
+    match options.team_filter {
+        None => true,
+        Some(ref teams) => {
+            let name = name.to_lowercase();
+            teams.iter().any(|team| name.contains(&team.to_lowercase()))
+        }
     }
+}
 
-    for event in relay_results {
-        let session = if event.session == 'P' { "Prelims" } else { "Finals" };
-        let (venue, meet_name, records) = if let Some(ref meta) = event.metadata {
-            (
-                meta.venue.clone().unwrap_or_default(),
-                meta.meet_name.clone().unwrap_or_default(),
-                meta.records.iter()
-                    .map(|r| r.trim_matches('=').trim())
-                    .collect::<Vec<_>>()
-                    .join(" | "),
-            )
-        } else {
-            (String::new(), String::new(), String::new())
-        };
+/// Checks if a swimmer passes the top_n and team filters
+pub(crate) fn include_swimmer(swimmer: &Swimmer, options: &OutputOptions) -> bool {
+    within_top_n(swimmer.place, options) && matches_team(&swimmer.school, options)
+}
+
+/// Checks if a relay team passes the top_n and team filters
+pub(crate) fn include_team(team: &RelayTeam, options: &OutputOptions) -> bool {
+    within_top_n(team.place, options) && matches_team(&team.team_name, options)
+}
 
-        writer.write_record([
-            &event.event_name,
+/// Sort key for a time: valid times in order, then DQ/NS/unparsable entries
+fn time_order(time: &str) -> (bool, u32) {
+    match parse_time_to_centiseconds(time) {
+        Some(centis) => (false, centis),
+        None => (true, 0),
+    }
+}
+
+/// Swimmers that pass the top_n and team filters, in the configured sort order
+pub(crate) fn output_swimmers<'a>(event: &'a EventResults, options: &OutputOptions) -> Vec<&'a Swimmer> {
+    let mut swimmers: Vec<&Swimmer> = event.swimmers.iter()
+        .filter(|s| include_swimmer(s, options))
+        .collect();
+    match options.sort_by {
+        SortKey::PageOrder => {}
+        SortKey::Place => swimmers.sort_by_key(|s| place_order(s.place)),
+        SortKey::FinalTime => swimmers.sort_by_key(|s| time_order(&s.final_time)),
+        SortKey::Name => swimmers.sort_by_key(|s| name_last_first(&s.name).to_lowercase()),
+        SortKey::School => swimmers.sort_by_key(|s| (s.school.to_lowercase(), place_order(s.place))),
+    }
+    if options.sort_by == SortKey::PageOrder && options.include_unplaced {
+        // Unplaced entries follow the top-N block
+        swimmers.sort_by_key(|s| s.place.is_none());
+    }
+    swimmers
+}
+
+/// Relay teams that pass the top_n and team filters, in the configured sort order
+/// (Name and School both sort by team name)
+pub(crate) fn output_teams<'a>(event: &'a RelayResults, options: &OutputOptions) -> Vec<&'a RelayTeam> {
+    let mut teams: Vec<&RelayTeam> = event.teams.iter()
+        .filter(|t| include_team(t, options))
+        .collect();
+    match options.sort_by {
+        SortKey::PageOrder => {}
+        SortKey::Place => teams.sort_by_key(|t| place_order(t.place)),
+        SortKey::FinalTime => teams.sort_by_key(|t| time_order(&t.final_time)),
+        SortKey::Name | SortKey::School => teams.sort_by_key(|t| (t.team_name.to_lowercase(), place_order(t.place))),
+    }
+    if options.sort_by == SortKey::PageOrder && options.include_unplaced {
+        // Unplaced entries follow the top-N block
+        teams.sort_by_key(|t| t.place.is_none());
+    }
+    teams
+}
+
+/// JSON document for a meet, mirroring ParsedResults with top_n applied
+#[derive(Serialize)]
+struct JsonResults<'a> {
+    meet_title: Option<&'a str>,
+    individual_results: Vec<JsonIndividualEvent<'a>>,
+    relay_results: Vec<JsonRelayEvent<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonIndividualEvent<'a> {
+    event_name: &'a str,
+    session: char,
+    metadata: Option<&'a EventMetadata>,
+    race_info: Option<&'a RaceInfo>,
+    swimmers: Vec<&'a Swimmer>,
+}
+
+#[derive(Serialize)]
+struct JsonRelayEvent<'a> {
+    event_name: &'a str,
+    session: char,
+    metadata: Option<&'a EventMetadata>,
+    race_info: Option<&'a RaceInfo>,
+    teams: Vec<&'a RelayTeam>,
+}
+
+impl<'a> JsonResults<'a> {
+    fn new(results: &'a ParsedResults, options: &OutputOptions) -> JsonResults<'a> {
+        JsonResults {
+            meet_title: results.meet_title.as_deref(),
+            individual_results: results.individual_results.iter()
+                .map(|event| JsonIndividualEvent {
+                    event_name: &event.event_name,
+                    session: event.session,
+                    metadata: event.metadata.as_ref(),
+                    race_info: event.race_info.as_ref(),
+                    swimmers: event.swimmers.iter()
+                        .filter(|s| include_swimmer(s, options))
+                        .collect(),
+                })
+                .collect(),
+            relay_results: results.relay_results.iter()
+                .map(|event| JsonRelayEvent {
+                    event_name: &event.event_name,
+                    session: event.session,
+                    metadata: event.metadata.as_ref(),
+                    race_info: event.race_info.as_ref(),
+                    teams: event.teams.iter()
+                        .filter(|t| include_team(t, options))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Writes a meet's results as one pretty-printed JSON document, honoring top_n
+pub fn write_results_json_to<W: Write>(
+    results: &ParsedResults,
+    options: &OutputOptions,
+    writer: &mut W,
+) -> Result<(), Box<dyn Error>> {
+    serde_json::to_writer_pretty(&mut *writer, &JsonResults::new(results, options))?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes a meet's results as one JSON document to a file
+pub fn write_results_json(
+    results: &ParsedResults,
+    options: &OutputOptions,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut file = BufWriter::new(File::create(path)?);
+    write_results_json_to(results, options, &mut file)?;
+    log::info!("Results written to {}", path.display());
+    Ok(())
+}
+
+// ============================================================================
+// JSON LINES OUTPUT
+// ============================================================================
+
+/// Event fields repeated on every JSON Lines record
+#[derive(Serialize)]
+struct EventContext<'a> {
+    meet: Option<&'a str>,
+    event_name: &'a str,
+    event_number: Option<u32>,
+    session: char,
+    gender: Option<&'a str>,
+    distance: Option<u16>,
+    course: Option<&'a str>,
+    stroke: Option<&'a str>,
+}
+
+impl<'a> EventContext<'a> {
+    fn new(meet: Option<&'a str>, event_name: &'a str, session: char, race_info: Option<&'a RaceInfo>) -> Self {
+        EventContext {
+            meet,
+            event_name,
+            event_number: race_info.map(|i| i.event_number),
             session,
-            &venue,
-            &meet_name,
-            &records,
-        ])?;
+            gender: race_info.and_then(|i| i.gender.as_deref()),
+            distance: race_info.and_then(|i| i.distance),
+            course: race_info.and_then(|i| i.course.as_deref()),
+            stroke: race_info.and_then(|i| i.stroke.as_deref()),
+        }
+    }
+}
+
+/// One swimmer or relay team flattened with its event context
+#[derive(Serialize)]
+struct JsonlRecord<'a, T: Serialize> {
+    #[serde(flatten)]
+    context: &'a EventContext<'a>,
+    record_type: &'static str,
+    #[serde(flatten)]
+    entry: &'a T,
+}
+
+/// Writes one JSON object per swimmer and relay team, one per line, with event context
+/// and nested splits, honoring top_n
+pub fn write_results_jsonl<W: Write>(
+    results: &ParsedResults,
+    options: &OutputOptions,
+    writer: &mut W,
+) -> Result<(), Box<dyn Error>> {
+    let meet = results.meet_title.as_deref();
+
+    for event in &results.individual_results {
+        let context = EventContext::new(meet, &event.event_name, event.session, event.race_info.as_ref());
+        for swimmer in event.swimmers.iter().filter(|s| include_swimmer(s, options)) {
+            let record = JsonlRecord { context: &context, record_type: "individual", entry: swimmer };
+            serde_json::to_writer(&mut *writer, &record)?;
+            writer.write_all(b"\n")?;
+        }
+    }
+
+    for event in &results.relay_results {
+        let context = EventContext::new(meet, &event.event_name, event.session, event.race_info.as_ref());
+        for team in event.teams.iter().filter(|t| include_team(t, options)) {
+            let record = JsonlRecord { context: &context, record_type: "relay", entry: team };
+            serde_json::to_writer(&mut *writer, &record)?;
+            writer.write_all(b"\n")?;
+        }
     }
 
     writer.flush()?;
-    println!("Metadata written to {}", METADATA_CSV_OUTPUT_FILE);
     Ok(())
 }
 
 // ============================================================================
-// INDIVIDUAL CSV OUTPUT
+// NDJSON OUTPUT
 // ============================================================================
 
-/// Writes individual event results to results.csv
-pub fn write_individual_csv(results: &[EventResults], options: &OutputOptions) -> Result<(), Box<dyn Error>> {
-    let max_splits = results.iter()
-        .flat_map(|e| e.swimmers.iter())
-        .map(|s| s.splits.len())
-        .max()
-        .unwrap_or(0);
+/// One NDJSON line: an individual or relay event tagged with its type
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum EventRecord<'a> {
+    Individual(&'a EventResults),
+    Relay(&'a RelayResults),
+}
+
+/// Writes one JSON object per event, one per line (individual events first).
+/// Call repeatedly on the same writer to append events as they arrive.
+pub fn write_results_ndjson<W: Write>(
+    individual_results: &[EventResults],
+    relay_results: &[RelayResults],
+    writer: &mut W,
+) -> Result<(), Box<dyn Error>> {
+    let records = individual_results.iter().map(EventRecord::Individual)
+        .chain(relay_results.iter().map(EventRecord::Relay));
 
-    let file = File::create(CSV_OUTPUT_FILE)?;
-    let mut writer = csv::Writer::from_writer(file);
+    for record in records {
+        serde_json::to_writer(&mut *writer, &record)?;
+        writer.write_all(b"\n")?;
+    }
 
-    let mut header: Vec<&str> = vec![
-        "event_name", "session", "event_number", "gender", "distance",
-        "course", "stroke", "place", "name", "year", "school", "seed_time", "final_time", "reaction_time"
-    ];
+    writer.flush()?;
+    Ok(())
+}
 
-    let split_headers: Vec<String> = (1..=max_splits).map(|i| format!("split{}", i)).collect();
-    let split_header_refs: Vec<&str> = split_headers.iter().map(|s| s.as_str()).collect();
-    header.extend(split_header_refs);
+// ============================================================================
+// MARKDOWN OUTPUT
+// ============================================================================
 
-    writer.write_record(&header)?;
+/// Individual and relay events in event-number order (prelims before finals)
+pub(crate) fn sorted_events(results: &ParsedResults) -> Vec<EventRef<'_>> {
+    order_events(&results.individual_results, &results.relay_results)
+}
 
-    for event in results {
-        let session = if event.session == 'P' { "Prelims" } else { "Finals" };
-
-        let (event_number, gender, distance, course, stroke) = if let Some(ref info) = event.race_info {
-            (
-                info.event_number,
-                info.gender.clone().unwrap_or_default(),
-                info.distance.unwrap_or(0),
-                info.course.clone().unwrap_or_default(),
-                info.stroke.clone().unwrap_or_default(),
-            )
-        } else {
-            (0, String::new(), 0, String::new(), String::new())
-        };
+/// Interleaves individual and relay events in event-number order (prelims before finals)
+fn order_events<'a>(individual_results: &'a [EventResults], relay_results: &'a [RelayResults]) -> Vec<EventRef<'a>> {
+    let mut events: Vec<EventRef> = individual_results.iter().map(EventRef::Individual)
+        .chain(relay_results.iter().map(EventRef::Relay))
+        .collect();
+    events.sort_by_key(|event| match event {
+        EventRef::Individual(r) => event_order(r.race_info.as_ref(), r.session),
+        EventRef::Relay(r) => event_order(r.race_info.as_ref(), r.session),
+    });
+    events
+}
+
+/// Escapes pipes so a value stays in one markdown table cell
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+/// Truncates a school name to max_school_len characters, marking the cut with "…"
+pub(crate) fn truncate_school(school: &str, options: &OutputOptions) -> String {
+    match options.max_school_len {
+        Some(max) if school.chars().count() > max => {
+            let kept: String = school.chars().take(max.saturating_sub(1)).collect();
+            format!("{}…", kept.trim_end())
+        }
+        _ => school.to_string(),
+    }
+}
 
-        for swimmer in &event.swimmers {
-            // Filter by placement if top_n is set (skip DQ/no-place swimmers)
-            if let Some(top_n) = options.top_n {
-                match swimmer.place {
-                    Some(place) if u32::from(place) > top_n => continue,
-                    None => continue,
-                    _ => {}
+/// Writes a markdown report: the meet title as an H1, then one H2 and table per event in
+/// event-number order, honoring top_n
+pub fn write_markdown_report<W: Write>(
+    results: &ParsedResults,
+    options: &OutputOptions,
+    writer: &mut W,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(writer, "# {}", results.meet_title.as_deref().unwrap_or("Results"))?;
+
+    for event in sorted_events(results) {
+        match event {
+            EventRef::Individual(event) => {
+                writeln!(writer, "\n## {} ({})\n", event.event_name, session_label(event.session))?;
+                writeln!(writer, "| Place | Name | Year | School | Time |")?;
+                writeln!(writer, "|---:|---|---|---|---:|")?;
+                for swimmer in output_swimmers(event, options) {
+                    writeln!(
+                        writer,
+                        "| {} | {} | {} | {} | {} |",
+                        swimmer.place.map(|p| p.to_string()).unwrap_or_else(|| "--".to_string()),
+                        markdown_cell(&options.name_format.apply(&swimmer.name)),
+                        markdown_cell(&swimmer.year),
+                        markdown_cell(&truncate_school(&swimmer.school, options)),
+                        markdown_cell(&swimmer.final_time),
+                    )?;
                 }
             }
-
-            let place_str = match swimmer.place {
-                Some(p) => p.to_string(),
-                None => String::new(),
-            };
-            let mut row: Vec<String> = vec![
-                event.event_name.clone(),
-                session.to_string(),
-                event_number.to_string(),
-                gender.clone(),
-                distance.to_string(),
-                course.clone(),
-                stroke.clone(),
-                place_str,
-                swimmer.name.clone(),
-                swimmer.year.clone(),
-                swimmer.school.clone(),
-                swimmer.seed_time.clone().unwrap_or_default(),
-                swimmer.final_time.clone(),
-                swimmer.reaction_time.clone().unwrap_or_default(),
-            ];
-
-            for i in 0..max_splits {
-                if i < swimmer.splits.len() {
-                    row.push(swimmer.splits[i].time.clone());
-                } else {
-                    row.push(String::new());
+            EventRef::Relay(event) => {
+                writeln!(writer, "\n## {} ({})\n", event.event_name, session_label(event.session))?;
+                writeln!(writer, "| Place | Team | Swimmers | Time |")?;
+                writeln!(writer, "|---:|---|---|---:|")?;
+                for team in output_teams(event, options) {
+                    let swimmers: Vec<String> = team.legs().iter()
+                        .filter(|s| !s.name.is_empty())
+                        .map(|s| options.name_format.apply(&s.name))
+                        .collect();
+                    writeln!(
+                        writer,
+                        "| {} | {} | {} | {} |",
+                        team.place.map(|p| p.to_string()).unwrap_or_else(|| "--".to_string()),
+                        markdown_cell(&truncate_school(&team.team_name, options)),
+                        markdown_cell(&swimmers.join(", ")),
+                        markdown_cell(&team.final_time),
+                    )?;
                 }
             }
-
-            writer.write_record(&row)?;
         }
     }
 
     writer.flush()?;
-    println!("Results written to {}", CSV_OUTPUT_FILE);
+    Ok(())
+}
+
+// ============================================================================
+// INDIVIDUAL CSV OUTPUT
+// ============================================================================
+
+/// Writes individual event results to results.csv (results.tsv when tab-delimited)
+pub fn write_individual_csv(results: &[EventResults], options: &OutputOptions) -> Result<(), Box<dyn Error>> {
+    let results: Vec<&EventResults> = results.iter().collect();
+    let path = data_file(CSV_OUTPUT_FILE, options);
+    write_individual_csv_to_file(&results, options, &PathBuf::from(&path))?;
+    log::info!("Results written to {}", path);
     Ok(())
 }
 
@@ -170,12 +862,155 @@ pub fn write_individual_csv(results: &[EventResults], options: &OutputOptions) -
 // OUTPUT FORMATTING
 // ============================================================================
 
+/// How swimmer names are written in output
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum NameFormat {
+    /// Name exactly as it appears on the results page
+    #[default]
+    AsIs,
+    /// "First Last"
+    FirstLast,
+    /// "Last, First"
+    LastFirst,
+}
+
+impl NameFormat {
+    /// Formats a name according to this format
+    pub fn apply(&self, name: &str) -> String {
+        match self {
+            NameFormat::AsIs => name.to_string(),
+            NameFormat::FirstLast => name_first_last(name),
+            NameFormat::LastFirst => name_last_first(name),
+        }
+    }
+}
+
+/// How time strings are written in output
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TimeFormat {
+    /// Time exactly as it appears on the results page (e.g. 1:02.31)
+    #[default]
+    Raw,
+    /// Decimal seconds (e.g. 62.31)
+    Seconds,
+    /// Whole centiseconds (e.g. 6231)
+    Centiseconds,
+}
+
+impl TimeFormat {
+    /// Formats a time string, keeping any record flag letters after it (1:39.50N ->
+    /// 99.50N); status strings (DQ, NS, NT) and empty times are unchanged
+    pub fn apply(&self, time: &str) -> String {
+        let value = time.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let flag = &time[value.len()..];
+        let parsed = match self {
+            TimeFormat::Raw => None,
+            _ => value.parse::<SwimTime>().ok(),
+        };
+        match (self, parsed) {
+            (TimeFormat::Seconds, Some(t)) => format!("{:.2}{}", t.as_seconds(), flag),
+            (TimeFormat::Centiseconds, Some(t)) => format!("{}{}", t.centiseconds(), flag),
+            _ => time.to_string(),
+        }
+    }
+}
+
+/// How event folders and files are named in folder output
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum FolderNaming {
+    /// Sanitized meet and event names plus a datetime id (e.g. Women_200_Free_20250327_101500)
+    #[default]
+    Unique,
+    /// No datetime ids: the sanitized meet name, event folders named by event number,
+    /// session, and event name (e.g. E03_F_Women_500_Yard_Freestyle), and fixed file
+    /// names (results.csv), so repeated runs write to the same paths
+    Deterministic,
+}
+
+/// What to do when a meet folder or file from an earlier run already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OnConflict {
+    /// Write into the existing folder, replacing files of the same name
+    #[default]
+    Overwrite,
+    /// Append "-1", "-2", ... until the name is unused
+    Suffix,
+}
+
+/// How relay splits are laid out in relay results rows
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RelaySplitLayout {
+    /// split1..splitN across the whole relay
+    #[default]
+    Flat,
+    /// swimmer1_split1.., swimmer2_split1.., grouped by leg
+    PerSwimmer,
+}
+
+/// Order of rows within each event
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SortKey {
+    /// Order rows appear on the results page
+    #[default]
+    PageOrder,
+    /// Place, with DQ/NS entries last
+    Place,
+    /// Final time, with DQ/NS entries last
+    FinalTime,
+    /// Swimmer name by last name (team name for relays)
+    Name,
+    /// School, then place (team name for relays)
+    School,
+}
+
 /// Configuration for output display and filtering
 #[derive(Debug, Clone)]
 pub struct OutputOptions {
     pub metadata: bool,
     /// Maximum placement to include (None = all placements)
     pub top_n: Option<u32>,
+    /// Keep DQ/NS entries (no place) when top_n is set, after the placed entries
+    pub include_unplaced: bool,
+    /// Only output DQ/NS entries (no place), e.g. for a DQ report; overrides top_n
+    pub only_unplaced: bool,
+    pub name_format: NameFormat,
+    pub naming: FolderNaming,
+    /// Write one CSV for all individual events and one for all relays instead of event folders
+    pub combined: bool,
+    /// Base directory for meet folders and files (None = current directory)
+    pub output_dir: Option<PathBuf>,
+    /// How an existing meet folder or file is handled (only deterministic names repeat)
+    pub on_conflict: OnConflict,
+    /// Append rows to existing results CSVs instead of replacing them
+    pub append: bool,
+    /// Also write long-format splits CSVs (one row per split) in event folders
+    pub splits_long: bool,
+    /// Include split columns in results output
+    pub include_splits: bool,
+    /// Include reaction time columns in results output
+    pub include_reaction: bool,
+    /// Truncate school and team names longer than this in reports (None = full names)
+    pub max_school_len: Option<usize>,
+    /// Only include swimmers and relay teams whose school or team name contains one of
+    /// these (case-insensitive; None = all teams). Applied alongside top_n, which still
+    /// compares event places.
+    pub team_filter: Option<Vec<String>>,
+    /// Order of rows within each event
+    pub sort_by: SortKey,
+    /// How relay splits are laid out in relay results rows
+    pub relay_split_layout: RelaySplitLayout,
+    /// Add seed_seconds and per-split `_seconds` columns (decimal seconds) to results rows
+    pub numeric_times: bool,
+    /// Also write athletes.csv (one row per athlete swim) into the meet folder
+    pub by_athlete: bool,
+    /// Also write exchanges.csv (negative and close relay exchanges) into the meet folder
+    pub exchange_report: bool,
+    /// Field delimiter for delimited output; tab switches file extensions to .tsv
+    pub delimiter: u8,
+    /// Add relay leadoff swims as individual events (see ParsedResults::include_leadoffs)
+    pub include_leadoffs: bool,
+    /// How times are written in CSV output (JSON output uses ParsedResults::format_times)
+    pub time_format: TimeFormat,
 }
 
 impl Default for OutputOptions {
@@ -183,13 +1018,34 @@ impl Default for OutputOptions {
         OutputOptions {
             metadata: true,
             top_n: None,
+            include_unplaced: false,
+            only_unplaced: false,
+            name_format: NameFormat::AsIs,
+            naming: FolderNaming::Unique,
+            combined: false,
+            output_dir: None,
+            on_conflict: OnConflict::Overwrite,
+            append: false,
+            splits_long: false,
+            include_splits: true,
+            include_reaction: true,
+            max_school_len: None,
+            team_filter: None,
+            sort_by: SortKey::PageOrder,
+            relay_split_layout: RelaySplitLayout::Flat,
+            numeric_times: false,
+            by_athlete: false,
+            exchange_report: false,
+            delimiter: b',',
+            include_leadoffs: false,
+            time_format: TimeFormat::Raw,
         }
     }
 }
 
 /// Prints individual results to stdout
 pub fn print_individual_results(results: &EventResults, options: &OutputOptions) {
-    let session_str = if results.session == 'P' { "Prelims" } else { "Finals" };
+    let session_str = session_label(results.session);
 
     if options.metadata {
         if let Some(ref meta) = results.metadata {
@@ -199,6 +1055,9 @@ pub fn print_individual_results(results: &EventResults, options: &OutputOptions)
             if let Some(ref meet) = meta.meet_name {
                 println!("Meet: {}", meet);
             }
+            if let Some(count) = meta.entry_count {
+                println!("Entries: {}", count);
+            }
             if !meta.records.is_empty() {
                 println!("Records:");
                 for record in &meta.records {
@@ -221,16 +1080,7 @@ pub fn print_individual_results(results: &EventResults, options: &OutputOptions)
     println!("\nEvent: {} {}", results.event_name, session_str);
     println!("{:-<80}", "");
 
-    for swimmer in &results.swimmers {
-        // Filter by placement if top_n is set (skip DQ/no-place swimmers)
-        if let Some(top_n) = options.top_n {
-            match swimmer.place {
-                Some(place) if u32::from(place) > top_n => continue,
-                None => continue,
-                _ => {}
-            }
-        }
-
+    for swimmer in output_swimmers(results, options) {
         let place_str = match swimmer.place {
             Some(p) => format!("{:2}", p),
             None => "--".to_string(),
@@ -238,12 +1088,16 @@ pub fn print_individual_results(results: &EventResults, options: &OutputOptions)
         println!(
             "{}. {:25} {:2} {:20} {}",
             place_str,
-            swimmer.name,
+            options.name_format.apply(&swimmer.name),
             swimmer.year,
             swimmer.school,
             swimmer.final_time
         );
 
+        if let Some(ref desc) = swimmer.dq_description {
+            println!("    {}", desc);
+        }
+
         if !swimmer.splits.is_empty() {
             print!("    Splits:");
             for (i, split) in swimmer.splits.iter().enumerate() {
@@ -258,111 +1112,16 @@ pub fn print_individual_results(results: &EventResults, options: &OutputOptions)
 // RELAY CSV OUTPUT
 // ============================================================================
 
-/// Writes relay results to relay_results.csv
+/// Writes relay results to relay_results.csv (relay_results.tsv when tab-delimited)
 pub fn write_relay_csv(results: &[RelayResults], options: &OutputOptions) -> Result<(), Box<dyn Error>> {
     if results.is_empty() {
         return Ok(());
     }
 
-    let max_splits = results.iter()
-        .flat_map(|e| e.teams.iter())
-        .map(|t| t.splits.len())
-        .max()
-        .unwrap_or(0);
-
-    let file = File::create(RELAY_CSV_OUTPUT_FILE)?;
-    let mut writer = csv::Writer::from_writer(file);
-
-    let mut header: Vec<&str> = vec![
-        "event_name", "session", "event_number", "gender", "distance", "course", "stroke",
-        "place", "team_name", "seed_time", "final_time", "dq_description",
-        "swimmer1_name", "swimmer1_year", "swimmer2_name", "swimmer2_year",
-        "swimmer3_name", "swimmer3_year", "swimmer4_name", "swimmer4_year",
-        "swimmer1_reaction", "swimmer2_reaction", "swimmer3_reaction", "swimmer4_reaction"
-    ];
-
-    let split_headers: Vec<String> = (1..=max_splits).map(|i| format!("split{}", i)).collect();
-    let split_header_refs: Vec<&str> = split_headers.iter().map(|s| s.as_str()).collect();
-    header.extend(split_header_refs);
-
-    writer.write_record(&header)?;
-
-    for event in results {
-        let session = if event.session == 'P' { "Prelims" } else { "Finals" };
-
-        let (event_number, gender, distance, course, stroke) = if let Some(ref info) = event.race_info {
-            (
-                info.event_number,
-                info.gender.clone().unwrap_or_default(),
-                info.distance.unwrap_or(0),
-                info.course.clone().unwrap_or_default(),
-                info.stroke.clone().unwrap_or_default(),
-            )
-        } else {
-            (0, String::new(), 0, String::new(), String::new())
-        };
-
-        for team in &event.teams {
-            // Filter by placement if top_n is set (skip DQ/no-place teams)
-            if let Some(top_n) = options.top_n {
-                match team.place {
-                    Some(place) if u32::from(place) > top_n => continue,
-                    None => continue,
-                    _ => {}
-                }
-            }
-
-            let place_str = match team.place {
-                Some(p) => p.to_string(),
-                None => String::new(),
-            };
-            let mut row: Vec<String> = vec![
-                event.event_name.clone(),
-                session.to_string(),
-                event_number.to_string(),
-                gender.clone(),
-                distance.to_string(),
-                course.clone(),
-                stroke.clone(),
-                place_str,
-                team.team_name.clone(),
-                team.seed_time.clone().unwrap_or_default(),
-                team.final_time.clone(),
-                team.dq_description.clone().unwrap_or_default(),
-            ];
-
-            for i in 0..4 {
-                if i < team.swimmers.len() {
-                    row.push(team.swimmers[i].name.clone());
-                    row.push(team.swimmers[i].year.clone());
-                } else {
-                    row.push(String::new());
-                    row.push(String::new());
-                }
-            }
-
-            for i in 0..4 {
-                if i < team.swimmers.len() {
-                    row.push(team.swimmers[i].reaction_time.clone().unwrap_or_default());
-                } else {
-                    row.push(String::new());
-                }
-            }
-
-            for i in 0..max_splits {
-                if i < team.splits.len() {
-                    row.push(team.splits[i].time.clone());
-                } else {
-                    row.push(String::new());
-                }
-            }
-
-            writer.write_record(&row)?;
-        }
-    }
-
-    writer.flush()?;
-    println!("Relay results written to {}", RELAY_CSV_OUTPUT_FILE);
+    let results: Vec<&RelayResults> = results.iter().collect();
+    let path = data_file(RELAY_CSV_OUTPUT_FILE, options);
+    write_relay_csv_to_file(&results, options, &PathBuf::from(&path))?;
+    log::info!("Relay results written to {}", path);
     Ok(())
 }
 
@@ -372,7 +1131,7 @@ pub fn write_relay_csv(results: &[RelayResults], options: &OutputOptions) -> Res
 
 /// Prints relay results to stdout
 pub fn print_relay_results(results: &RelayResults, options: &OutputOptions) {
-    let session_str = if results.session == 'P' { "Prelims" } else { "Finals" };
+    let session_str = session_label(results.session);
 
     if options.metadata {
         if let Some(ref meta) = results.metadata {
@@ -382,6 +1141,9 @@ pub fn print_relay_results(results: &RelayResults, options: &OutputOptions) {
             if let Some(ref meet) = meta.meet_name {
                 println!("Meet: {}", meet);
             }
+            if let Some(count) = meta.entry_count {
+                println!("Entries: {}", count);
+            }
             if !meta.records.is_empty() {
                 println!("Records:");
                 for record in &meta.records {
@@ -403,16 +1165,7 @@ pub fn print_relay_results(results: &RelayResults, options: &OutputOptions) {
     println!("\nEvent: {} {}", results.event_name, session_str);
     println!("{:-<80}", "");
 
-    for team in &results.teams {
-        // Filter by placement if top_n is set (skip DQ/no-place teams)
-        if let Some(top_n) = options.top_n {
-            match team.place {
-                Some(place) if u32::from(place) > top_n => continue,
-                None => continue,
-                _ => {}
-            }
-        }
-
+    for team in output_teams(results, options) {
         let place_str = match team.place {
             Some(p) => format!("{:2}", p),
             None => "--".to_string(),
@@ -430,12 +1183,14 @@ pub fn print_relay_results(results: &RelayResults, options: &OutputOptions) {
 
         for (i, swimmer) in team.swimmers.iter().enumerate() {
             let reaction = swimmer.reaction_time.as_deref().unwrap_or("");
+            let alternate = if i >= RELAY_LEGS { " (alternate)" } else { "" };
             println!(
-                "    {}) {:25} {:2} {}",
+                "    {}) {:25} {:2} {}{}",
                 i + 1,
-                swimmer.name,
+                options.name_format.apply(&swimmer.name),
                 swimmer.year,
-                reaction
+                reaction,
+                alternate
             );
         }
 
@@ -453,170 +1208,504 @@ pub fn print_relay_results(results: &RelayResults, options: &OutputOptions) {
 // FOLDER-BASED CSV OUTPUT
 // ============================================================================
 
-/// Writes results to organized folder structure
-/// Creates: MeetName_datetime_random/EventName_datetime_random/files.csv
+/// Returns the deterministic folder name for an event group, e.g.
+/// "E03_F_Women_500_Yard_Freestyle"
+fn event_number_stem(
+    event_name: &str,
+    ind_results: &[&EventResults],
+    rel_results: &[&RelayResults],
+) -> String {
+    let race_info = ind_results.iter().find_map(|r| r.race_info.as_ref())
+        .or_else(|| rel_results.iter().find_map(|r| r.race_info.as_ref()));
+    let event_number = race_info.map(|info| info.event_number).unwrap_or(0);
+
+    let mut sessions: Vec<char> = ind_results.iter().map(|r| r.session)
+        .chain(rel_results.iter().map(|r| r.session))
+        .collect();
+    sessions.sort_by_key(|s| if *s == 'P' { 0 } else { 1 });
+    sessions.dedup();
+
+    // Headlines usually start with "Event N", which the number prefix already covers
+    let tokens: Vec<&str> = event_name.split_whitespace().collect();
+    let name = match tokens.as_slice() {
+        [first, number, rest @ ..] if first.eq_ignore_ascii_case("event") && number.parse::<u32>().is_ok() => {
+            rest.join(" ")
+        }
+        _ => event_name.to_string(),
+    };
+
+    format!("E{:02}_{}_{}", event_number, sessions.iter().collect::<String>(), sanitize_name(&name))
+}
+
+/// Returns the sanitized meet name used for meet folders and files, with a datetime id
+/// unless naming is deterministic
+pub fn meet_output_name(meet_title: Option<&str>, options: &OutputOptions) -> String {
+    let meet_name = meet_title
+        .map(sanitize_name)
+        .unwrap_or_else(|| "UnknownMeet".to_string());
+    match options.naming {
+        FolderNaming::Unique => format!("{}_{}", meet_name, generate_unique_id()),
+        FolderNaming::Deterministic => meet_name,
+    }
+}
+
+/// Returns the path of a meet's output folder or file stem under the output directory
+pub fn meet_output_path(meet_title: Option<&str>, options: &OutputOptions) -> PathBuf {
+    let name = meet_output_name(meet_title, options);
+    match options.output_dir {
+        Some(ref dir) => dir.join(name),
+        None => PathBuf::from(name),
+    }
+}
+
+/// Applies the conflict policy to a meet output path, e.g. "Meet.xlsx" -> "Meet-1.xlsx"
+/// when suffixing and "Meet.xlsx" already exists
+pub fn resolve_output_conflict(path: PathBuf, options: &OutputOptions) -> PathBuf {
+    if options.on_conflict == OnConflict::Overwrite || !path.exists() {
+        return path;
+    }
+
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+
+    let mut counter = 1;
+    loop {
+        let candidate = path.with_file_name(format!("{}-{}{}", stem, counter, extension));
+        if !candidate.exists() {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+/// Creates the output directory if needed and checks that files can be written to it,
+/// so an unwritable directory fails before any results are fetched
+pub fn prepare_output_dir(options: &OutputOptions) -> Result<(), Box<dyn Error>> {
+    let Some(ref dir) = options.output_dir else {
+        return Ok(());
+    };
+
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Cannot create output directory {}: {}", dir.display(), e))?;
+
+    let probe = dir.join(".write_test");
+    File::create(&probe)
+        .map_err(|e| format!("Output directory {} is not writable: {}", dir.display(), e))?;
+    fs::remove_file(&probe)?;
+    Ok(())
+}
+
+/// Writes results to organized folder structure, with meet_summary.csv and manifest.json
+/// at the meet level, returning the manifest
+/// Creates: MeetName_datetime_random/EventName_datetime_random/results_EventName_datetime_random.csv
+/// or, with deterministic naming: MeetName/E03_F_EventName/results.csv
 pub fn write_results_to_folders(
     individual_results: &[EventResults],
     relay_results: &[RelayResults],
     meet_title: Option<&str>,
+    source_url: Option<&str>,
     options: &OutputOptions,
-) -> Result<PathBuf, Box<dyn Error>> {
-    let meet_id = generate_unique_id();
+) -> Result<Manifest, Box<dyn Error>> {
+    let mut writer = FolderWriter::new(meet_title, options)?;
+    if let Some(url) = source_url {
+        writer.set_source_url(url);
+    }
+    writer.write(individual_results, relay_results)?;
+    writer.finish()
+}
 
-    // Create meet folder name
-    let meet_name = meet_title
-        .map(|t| sanitize_name(t))
-        .unwrap_or_else(|| "UnknownMeet".to_string());
-    let meet_folder_name = format!("{}_{}", meet_name, meet_id);
-    let meet_path = PathBuf::from(&meet_folder_name);
+/// Describes a meet folder written by FolderWriter, so tools can find each event's files
+/// without parsing folder names
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Manifest {
+    pub meet_title: Option<String>,
+    /// When the meet folder was written (RFC 3339; None in deterministic mode)
+    pub scraped_at: Option<String>,
+    pub source_url: Option<String>,
+    /// Meet folder the manifest describes
+    #[serde(skip)]
+    pub meet_path: PathBuf,
+    pub events: Vec<ManifestEvent>,
+}
 
-    fs::create_dir_all(&meet_path)?;
-    println!("Created meet folder: {}", meet_folder_name);
+/// One event folder in a Manifest
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManifestEvent {
+    /// Folder name, relative to the meet folder
+    pub folder: String,
+    pub event_number: Option<u32>,
+    pub event_name: String,
+    /// Session labels of the results in the folder (e.g. ["Prelims", "Finals"])
+    pub sessions: Vec<String>,
+    pub files: Vec<ManifestFile>,
+}
+
+/// One file in an event folder, with the number of data rows written to it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManifestFile {
+    pub name: String,
+    pub rows: usize,
+}
+
+/// Writes events into one meet folder across several calls (e.g. as a meet streams in),
+/// keeping event folder names unique and manifest.json up to date; finish writes
+/// meet_summary.csv once every event is in
+pub struct FolderWriter {
+    meet_path: PathBuf,
+    options: OutputOptions,
+    used_stems: HashSet<String>,
+    manifest: Manifest,
+    /// meet_summary.csv rows of the events written so far, with their event_order keys
+    summary: Vec<((u32, u8), [String; 8])>,
+}
+
+impl FolderWriter {
+    /// Creates the meet folder
+    pub fn new(meet_title: Option<&str>, options: &OutputOptions) -> Result<FolderWriter, Box<dyn Error>> {
+        let meet_path = resolve_output_conflict(meet_output_path(meet_title, options), options);
 
-    // Group results by event name (combining individual and relay)
-    let mut event_groups: HashMap<String, (Vec<&EventResults>, Vec<&RelayResults>)> = HashMap::new();
+        fs::create_dir_all(&meet_path)?;
+        log::info!("Created meet folder: {}", meet_path.display());
+
+        let manifest = Manifest {
+            meet_title: meet_title.map(str::to_string),
+            scraped_at: (options.naming == FolderNaming::Unique).then(|| chrono::Local::now().to_rfc3339()),
+            source_url: None,
+            meet_path: meet_path.clone(),
+            events: Vec::new(),
+        };
 
-    for result in individual_results {
-        let event_name = &result.event_name;
-        event_groups
-            .entry(event_name.clone())
-            .or_insert_with(|| (Vec::new(), Vec::new()))
-            .0
-            .push(result);
+        Ok(FolderWriter {
+            meet_path,
+            options: options.clone(),
+            used_stems: HashSet::new(),
+            manifest,
+            summary: Vec::new(),
+        })
     }
 
-    for result in relay_results {
-        let event_name = &result.event_name;
-        event_groups
-            .entry(event_name.clone())
-            .or_insert_with(|| (Vec::new(), Vec::new()))
-            .1
-            .push(result);
+    /// Returns the meet folder path
+    pub fn meet_path(&self) -> &Path {
+        &self.meet_path
     }
 
-    // Process each event
-    for (event_name, (ind_results, rel_results)) in &event_groups {
-        let event_id = generate_unique_id();
-        let sanitized_event = sanitize_name(event_name);
-        let event_folder_name = format!("{}_{}", sanitized_event, event_id);
-        let event_path = meet_path.join(&event_folder_name);
+    /// Records the URL the results were scraped from in the manifest
+    pub fn set_source_url(&mut self, url: &str) {
+        self.manifest.source_url = Some(url.to_string());
+    }
 
-        fs::create_dir_all(&event_path)?;
+    /// Returns the manifest of the event folders written so far
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
 
-        let file_suffix = format!("{}_{}", sanitized_event, event_id);
+    /// Writes one folder per event, combining results that share an event name
+    pub fn write(
+        &mut self,
+        individual_results: &[EventResults],
+        relay_results: &[RelayResults],
+    ) -> Result<(), Box<dyn Error>> {
+        let events = individual_results.iter().map(EventRef::Individual)
+            .chain(relay_results.iter().map(EventRef::Relay));
+        for event in events {
+            let order = match event {
+                EventRef::Individual(r) => event_order(r.race_info.as_ref(), r.session),
+                EventRef::Relay(r) => event_order(r.race_info.as_ref(), r.session),
+            };
+            self.summary.push((order, event_summary_row(&event, &self.options)));
+        }
 
-        // Write individual results if present
-        if !ind_results.is_empty() {
-            let ind_file = event_path.join(format!("results_{}.csv", file_suffix));
-            write_individual_csv_to_file(ind_results, options, &ind_file)?;
+        let options = &self.options;
+
+        // Group results by event name (combining individual and relay)
+        let mut event_groups: BTreeMap<String, (Vec<&EventResults>, Vec<&RelayResults>)> = BTreeMap::new();
+
+        // With a team filter, events with no matching entries get no folder
+        let filtering = options.team_filter.is_some();
+        let individual_results = individual_results.iter()
+            .filter(|r| !filtering || r.swimmers.iter().any(|s| include_swimmer(s, options)));
+        let relay_results = relay_results.iter()
+            .filter(|r| !filtering || r.teams.iter().any(|t| include_team(t, options)));
+
+        for result in individual_results {
+            let event_name = &result.event_name;
+            event_groups
+                .entry(event_name.clone())
+                .or_insert_with(|| (Vec::new(), Vec::new()))
+                .0
+                .push(result);
         }
 
-        // Write relay results if present
-        if !rel_results.is_empty() {
-            let relay_file = event_path.join(format!("results_{}.csv", file_suffix));
-            write_relay_csv_to_file(rel_results, options, &relay_file)?;
+        for result in relay_results {
+            let event_name = &result.event_name;
+            event_groups
+                .entry(event_name.clone())
+                .or_insert_with(|| (Vec::new(), Vec::new()))
+                .1
+                .push(result);
         }
 
-        // Write metadata if enabled
-        if options.metadata {
-            let meta_file = event_path.join(format!("metadata_{}.csv", file_suffix));
-            write_metadata_csv_to_file(ind_results, rel_results, &meta_file)?;
+        // Process each event
+        for (event_name, (ind_results, rel_results)) in &event_groups {
+            let stem = match options.naming {
+                FolderNaming::Unique => format!("{}_{}", sanitize_name(event_name), generate_unique_id()),
+                FolderNaming::Deterministic => event_number_stem(event_name, ind_results, rel_results),
+            };
+
+            // Append a counter when two events would share a folder name
+            let mut event_folder_name = stem.clone();
+            let mut counter = 2;
+            while self.used_stems.contains(&event_folder_name) {
+                event_folder_name = format!("{}_{}", stem, counter);
+                counter += 1;
+            }
+            self.used_stems.insert(event_folder_name.clone());
+            let event_path = self.meet_path.join(&event_folder_name);
+
+            // Deterministic folders hold fixed file names (results.csv); otherwise file
+            // names repeat the folder name
+            let file_name = |kind: &str| match options.naming {
+                FolderNaming::Unique => data_file(&format!("{}_{}", kind, event_folder_name), options),
+                FolderNaming::Deterministic => data_file(kind, options),
+            };
+
+            fs::create_dir_all(&event_path)?;
+            let mut files: Vec<ManifestFile> = Vec::new();
+
+            // Write individual results if present
+            if !ind_results.is_empty() {
+                let name = file_name(CSV_OUTPUT_FILE);
+                write_individual_csv_to_file(ind_results, options, &event_path.join(&name))?;
+                let rows = ind_results.iter().map(|e| output_swimmers(e, options).len()).sum();
+                files.push(ManifestFile { name, rows });
+            }
+
+            // Write relay results if present, to their own file when the event also has
+            // individual results
+            if !rel_results.is_empty() {
+                let prefix = if ind_results.is_empty() { CSV_OUTPUT_FILE } else { RELAY_CSV_OUTPUT_FILE };
+                let name = file_name(prefix);
+                write_relay_csv_to_file(rel_results, options, &event_path.join(&name))?;
+                let rows = rel_results.iter().map(|e| output_teams(e, options).len()).sum();
+                files.push(ManifestFile { name, rows });
+            }
+
+            // Write long-format splits if enabled
+            if options.splits_long {
+                if !ind_results.is_empty() {
+                    let name = file_name("splits");
+                    write_splits_csv(ind_results, options, &event_path.join(&name))?;
+                    let rows = ind_results.iter()
+                        .flat_map(|e| e.swimmers.iter().filter(|s| include_swimmer(s, options)))
+                        .map(|s| s.splits.len())
+                        .sum();
+                    files.push(ManifestFile { name, rows });
+                }
+                if !rel_results.is_empty() {
+                    let name = file_name("relay_splits");
+                    write_relay_splits_csv(rel_results, options, &event_path.join(&name))?;
+                    let rows = rel_results.iter()
+                        .flat_map(|e| e.teams.iter().filter(|t| include_team(t, options)))
+                        .map(|t| t.splits.len())
+                        .sum();
+                    files.push(ManifestFile { name, rows });
+                }
+            }
+
+            // Write metadata if enabled
+            if options.metadata {
+                let name = file_name(METADATA_CSV_OUTPUT_FILE);
+                write_metadata_csv_to_file(ind_results, rel_results, options, &event_path.join(&name))?;
+                files.push(ManifestFile { name, rows: ind_results.len() + rel_results.len() });
+            }
+
+            let event_number = ind_results.iter().filter_map(|r| r.race_info.as_ref())
+                .chain(rel_results.iter().filter_map(|r| r.race_info.as_ref()))
+                .map(|info| info.event_number)
+                .next();
+            let mut sessions: Vec<char> = ind_results.iter().map(|r| r.session)
+                .chain(rel_results.iter().map(|r| r.session))
+                .collect();
+            sessions.sort_by_key(|s| if *s == 'P' { 0 } else { 1 });
+            sessions.dedup();
+
+            self.manifest.events.push(ManifestEvent {
+                folder: event_folder_name.clone(),
+                event_number,
+                event_name: event_name.clone(),
+                sessions: sessions.into_iter().map(|s| session_label(s).to_string()).collect(),
+                files,
+            });
+
+            log::info!("Created event folder: {}", event_folder_name);
         }
 
-        println!("  Created event folder: {}", event_folder_name);
+        self.write_manifest()
     }
 
-    Ok(meet_path)
-}
-
-/// Writes individual results to a specific file path
-fn write_individual_csv_to_file(
-    results: &[&EventResults],
-    options: &OutputOptions,
-    path: &PathBuf,
-) -> Result<(), Box<dyn Error>> {
-    let max_splits = results.iter()
-        .flat_map(|e| e.swimmers.iter())
-        .map(|s| s.splits.len())
-        .max()
-        .unwrap_or(0);
+    /// Writes meet_summary.csv for every event written, in event order, and returns the
+    /// manifest
+    pub fn finish(mut self) -> Result<Manifest, Box<dyn Error>> {
+        self.summary.sort_by_key(|(order, _)| *order);
+        let path = self.meet_path.join(data_file(MEET_SUMMARY_CSV_FILE, &self.options));
+        write_summary_rows(self.summary.into_iter().map(|(_, row)| row), &self.options, &path)?;
+        Ok(self.manifest)
+    }
 
-    let file = File::create(path)?;
-    let mut writer = csv::Writer::from_writer(file);
+    /// Writes manifest.json at the meet root, replacing any earlier version
+    fn write_manifest(&self) -> Result<(), Box<dyn Error>> {
+        let file = BufWriter::new(File::create(self.meet_path.join(MANIFEST_FILE))?);
+        serde_json::to_writer_pretty(file, &self.manifest)?;
+        Ok(())
+    }
+}
 
-    let mut header: Vec<&str> = vec![
-        "event_name", "session", "event_number", "gender", "distance",
-        "course", "stroke", "place", "name", "year", "school", "seed_time", "final_time", "reaction_time"
-    ];
+// ============================================================================
+// COMBINED CSV OUTPUT
+// ============================================================================
 
-    let split_headers: Vec<String> = (1..=max_splits).map(|i| format!("split{}", i)).collect();
-    let split_header_refs: Vec<&str> = split_headers.iter().map(|s| s.as_str()).collect();
-    header.extend(split_header_refs);
+/// Sort key placing events in event-number order (prelims before finals), unnumbered last
+fn event_order(race_info: Option<&RaceInfo>, session: char) -> (u32, u8) {
+    let number = race_info.map(|info| info.event_number).unwrap_or(u32::MAX);
+    (number, if session == 'P' { 0 } else { 1 })
+}
 
-    writer.write_record(&header)?;
+/// Sort key placing entries by place, with DQ/NS entries last
+fn place_order(place: Option<u8>) -> u16 {
+    place.map(u16::from).unwrap_or(u16::MAX)
+}
 
-    for event in results {
-        let session = if event.session == 'P' { "Prelims" } else { "Finals" };
-
-        let (event_number, gender, distance, course, stroke) = if let Some(ref info) = event.race_info {
-            (
-                info.event_number,
-                info.gender.clone().unwrap_or_default(),
-                info.distance.unwrap_or(0),
-                info.course.clone().unwrap_or_default(),
-                info.stroke.clone().unwrap_or_default(),
-            )
-        } else {
-            (0, String::new(), 0, String::new(), String::new())
-        };
+/// Writes every individual swim to all_results.csv and every relay to all_relays.csv in
+/// the meet folder, ordered by event number then place, instead of one folder per event
+pub fn write_combined_csv(
+    individual_results: &[EventResults],
+    relay_results: &[RelayResults],
+    meet_title: Option<&str>,
+    options: &OutputOptions,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let meet_path = resolve_output_conflict(meet_output_path(meet_title, options), options);
+    fs::create_dir_all(&meet_path)?;
 
-        for swimmer in &event.swimmers {
-            // Filter by placement if top_n is set (skip DQ/no-place swimmers)
-            if let Some(top_n) = options.top_n {
-                match swimmer.place {
-                    Some(place) if u32::from(place) > top_n => continue,
-                    None => continue,
-                    _ => {}
-                }
+    let mut individual: Vec<&EventResults> = individual_results.iter().collect();
+    individual.sort_by_key(|r| event_order(r.race_info.as_ref(), r.session));
+    let mut relay: Vec<&RelayResults> = relay_results.iter().collect();
+    relay.sort_by_key(|r| event_order(r.race_info.as_ref(), r.session));
+
+    if !individual.is_empty() {
+        let (mut writer, max_splits) = open_results_csv(
+            &meet_path.join(data_file(COMBINED_CSV_FILE, options)),
+            options,
+            individual_header,
+            max_individual_splits(&individual, options),
+        )?;
+
+        for event in &individual {
+            let mut swimmers = output_swimmers(event, options);
+            if options.sort_by == SortKey::PageOrder {
+                swimmers.sort_by_key(|s| place_order(s.place));
             }
+            for swimmer in swimmers {
+                writer.write_record(individual_row(event, swimmer, options, max_splits))?;
+            }
+        }
+        writer.flush()?;
+    }
 
-            let place_str = match swimmer.place {
-                Some(p) => p.to_string(),
-                None => String::new(),
-            };
-            let mut row: Vec<String> = vec![
-                event.event_name.clone(),
-                session.to_string(),
-                event_number.to_string(),
-                gender.clone(),
-                distance.to_string(),
-                course.clone(),
-                stroke.clone(),
-                place_str,
-                swimmer.name.clone(),
-                swimmer.year.clone(),
-                swimmer.school.clone(),
-                swimmer.seed_time.clone().unwrap_or_default(),
-                swimmer.final_time.clone(),
-                swimmer.reaction_time.clone().unwrap_or_default(),
-            ];
-
-            for i in 0..max_splits {
-                if i < swimmer.splits.len() {
-                    row.push(swimmer.splits[i].time.clone());
-                } else {
-                    row.push(String::new());
-                }
+    if !relay.is_empty() {
+        let (mut writer, max_splits) = open_results_csv(
+            &meet_path.join(data_file(COMBINED_RELAY_CSV_FILE, options)),
+            options,
+            relay_header,
+            max_relay_splits(&relay, options),
+        )?;
+
+        for event in &relay {
+            let mut teams = output_teams(event, options);
+            if options.sort_by == SortKey::PageOrder {
+                teams.sort_by_key(|t| place_order(t.place));
+            }
+            for team in teams {
+                writer.write_record(relay_row(event, team, options, max_splits))?;
             }
+        }
+        writer.flush()?;
+    }
 
-            writer.write_record(&row)?;
+    if options.metadata {
+        write_metadata_csv_to_file(&individual, &relay, options, &meet_path.join(data_file(METADATA_CSV_OUTPUT_FILE, options)))?;
+    }
+
+    log::info!("Combined results written to {}", meet_path.display());
+    Ok(meet_path)
+}
+
+/// Writes individual rows that pass the top_n and team filters, in sort order
+fn write_individual_rows<W: Write>(
+    writer: &mut csv::Writer<W>,
+    results: &[&EventResults],
+    options: &OutputOptions,
+    max_splits: usize,
+) -> Result<(), Box<dyn Error>> {
+    for event in results {
+        for swimmer in output_swimmers(event, options) {
+            writer.write_record(individual_row(event, swimmer, options, max_splits))?;
         }
     }
+    writer.flush()?;
+    Ok(())
+}
 
+/// Writes relay rows that pass the top_n and team filters, in sort order
+fn write_relay_rows<W: Write>(
+    writer: &mut csv::Writer<W>,
+    results: &[&RelayResults],
+    options: &OutputOptions,
+    max_splits: usize,
+) -> Result<(), Box<dyn Error>> {
+    for event in results {
+        for team in output_teams(event, options) {
+            writer.write_record(relay_row(event, team, options, max_splits))?;
+        }
+    }
     writer.flush()?;
     Ok(())
 }
 
+/// Writes individual results as CSV to any writer (e.g. stdout or a buffer)
+pub fn write_individual_csv_to<W: Write>(
+    results: &[&EventResults],
+    options: &OutputOptions,
+    out: W,
+) -> Result<(), Box<dyn Error>> {
+    let max_splits = max_individual_splits(results, options);
+    let mut writer = csv_writer(options).from_writer(out);
+    writer.write_record(individual_header(options, max_splits))?;
+    write_individual_rows(&mut writer, results, options, max_splits)
+}
+
+/// Writes relay results as CSV to any writer (e.g. stdout or a buffer)
+pub fn write_relay_csv_to<W: Write>(
+    results: &[&RelayResults],
+    options: &OutputOptions,
+    out: W,
+) -> Result<(), Box<dyn Error>> {
+    let max_splits = max_relay_splits(results, options);
+    let mut writer = csv_writer(options).from_writer(out);
+    writer.write_record(relay_header(options, max_splits))?;
+    write_relay_rows(&mut writer, results, options, max_splits)
+}
+
+/// Writes individual results to a specific file path
+fn write_individual_csv_to_file(
+    results: &[&EventResults],
+    options: &OutputOptions,
+    path: &PathBuf,
+) -> Result<(), Box<dyn Error>> {
+    let (mut writer, max_splits) =
+        open_results_csv(path, options, individual_header, max_individual_splits(results, options))?;
+    write_individual_rows(&mut writer, results, options, max_splits)
+}
+
 /// Writes relay results to a specific file path
 fn write_relay_csv_to_file(
     results: &[&RelayResults],
@@ -627,166 +1716,559 @@ fn write_relay_csv_to_file(
         return Ok(());
     }
 
-    let max_splits = results.iter()
-        .flat_map(|e| e.teams.iter())
-        .map(|t| t.splits.len())
-        .max()
-        .unwrap_or(0);
+    let (mut writer, max_splits) =
+        open_results_csv(path, options, relay_header, max_relay_splits(results, options))?;
+    write_relay_rows(&mut writer, results, options, max_splits)
+}
 
-    let file = File::create(path)?;
-    let mut writer = csv::Writer::from_writer(file);
+/// Writes metadata to a specific file path
+fn write_metadata_csv_to_file(
+    individual_results: &[&EventResults],
+    relay_results: &[&RelayResults],
+    options: &OutputOptions,
+    path: &PathBuf,
+) -> Result<(), Box<dyn Error>> {
+    write_metadata_csv_to(individual_results, relay_results, options, File::create(path)?)
+}
 
-    let mut header: Vec<&str> = vec![
-        "event_name", "session", "event_number", "gender", "distance", "course", "stroke",
-        "place", "team_name", "seed_time", "final_time", "dq_description",
-        "swimmer1_name", "swimmer1_year", "swimmer2_name", "swimmer2_year",
-        "swimmer3_name", "swimmer3_year", "swimmer4_name", "swimmer4_year",
-        "swimmer1_reaction", "swimmer2_reaction", "swimmer3_reaction", "swimmer4_reaction"
-    ];
+// ============================================================================
+// MEET SUMMARY CSV OUTPUT
+// ============================================================================
 
-    let split_headers: Vec<String> = (1..=max_splits).map(|i| format!("split{}", i)).collect();
-    let split_header_refs: Vec<&str> = split_headers.iter().map(|s| s.as_str()).collect();
-    header.extend(split_header_refs);
+const MEET_SUMMARY_HEADER: [&str; 8] = [
+    "event_number", "event_name", "session", "winner", "winner_school",
+    "winning_time", "margin", "record",
+];
+
+/// Returns the placed entry with the lowest place and the runner-up, each as
+/// (name, school, final time); a tie for first gives a runner-up with the same time
+fn top_two<'a>(entries: &mut [(Option<u8>, &'a str, &'a str, &'a str)]) -> (Option<(&'a str, &'a str, &'a str)>, Option<&'a str>) {
+    entries.sort_by_key(|&(place, ..)| place_order(place));
+    let mut placed = entries.iter().filter(|(place, ..)| place.is_some());
+    let winner = placed.next().map(|&(_, name, school, time)| (name, school, time));
+    let runner_up = placed.next().map(|&(.., time)| time);
+    (winner, runner_up)
+}
 
-    writer.write_record(&header)?;
+/// Whether a winning time carries a record flag letter (e.g. "1:39.50N") or beats a
+/// record time listed in the event header
+fn is_record(time: &str, metadata: Option<&EventMetadata>) -> bool {
+    if time.trim_end().ends_with(|c: char| c.is_ascii_alphabetic()) {
+        return true;
+    }
+    let (Ok(time), Some(metadata)) = (time.parse::<SwimTime>(), metadata) else {
+        return false;
+    };
+    metadata.record_times().iter().any(|record| time < *record)
+}
 
-    for event in results {
-        let session = if event.session == 'P' { "Prelims" } else { "Finals" };
-
-        let (event_number, gender, distance, course, stroke) = if let Some(ref info) = event.race_info {
-            (
-                info.event_number,
-                info.gender.clone().unwrap_or_default(),
-                info.distance.unwrap_or(0),
-                info.course.clone().unwrap_or_default(),
-                info.stroke.clone().unwrap_or_default(),
-            )
-        } else {
-            (0, String::new(), 0, String::new(), String::new())
-        };
+/// Builds one summary row: event context, winner, winning time, margin over second place,
+/// and the record flag
+fn summary_row<'a>(
+    event_name: &str,
+    session: char,
+    race_info: Option<&RaceInfo>,
+    metadata: Option<&EventMetadata>,
+    entries: &mut [(Option<u8>, &'a str, &'a str, &'a str)],
+    time_format: TimeFormat,
+) -> [String; 8] {
+    let (winner, runner_up) = top_two(entries);
+    let (name, school, time) = winner.unwrap_or_default();
+
+    let margin = match (time.parse::<SwimTime>(), runner_up.map(str::parse::<SwimTime>)) {
+        (Ok(first), Some(Ok(second))) => second.checked_sub(first)
+            .map(|m| time_format.apply(&m.to_string()))
+            .unwrap_or_default(),
+        _ => String::new(),
+    };
+
+    [
+        race_info.map(|i| i.event_number.to_string()).unwrap_or_default(),
+        event_name.to_string(),
+        session_label(session).to_string(),
+        name.to_string(),
+        school.to_string(),
+        time_format.apply(time),
+        margin,
+        (winner.is_some() && is_record(time, metadata)).to_string(),
+    ]
+}
 
-        for team in &event.teams {
-            // Filter by placement if top_n is set (skip DQ/no-place teams)
-            if let Some(top_n) = options.top_n {
-                match team.place {
-                    Some(place) if u32::from(place) > top_n => continue,
-                    None => continue,
-                    _ => {}
-                }
-            }
+/// Builds an event's summary row (see summary_row)
+fn event_summary_row(event: &EventRef, options: &OutputOptions) -> [String; 8] {
+    match *event {
+        EventRef::Individual(r) => {
+            let mut entries: Vec<_> = r.swimmers.iter()
+                .map(|s| (s.place, s.name.as_str(), s.school.as_str(), s.final_time.as_str()))
+                .collect();
+            summary_row(&r.event_name, r.session, r.race_info.as_ref(), r.metadata.as_ref(), &mut entries, options.time_format)
+        }
+        EventRef::Relay(r) => {
+            let mut entries: Vec<_> = r.teams.iter()
+                .map(|t| (t.place, t.team_name.as_str(), "", t.final_time.as_str()))
+                .collect();
+            summary_row(&r.event_name, r.session, r.race_info.as_ref(), r.metadata.as_ref(), &mut entries, options.time_format)
+        }
+    }
+}
 
-            let place_str = match team.place {
-                Some(p) => p.to_string(),
-                None => String::new(),
-            };
-            let mut row: Vec<String> = vec![
-                event.event_name.clone(),
-                session.to_string(),
-                event_number.to_string(),
-                gender.clone(),
-                distance.to_string(),
-                course.clone(),
-                stroke.clone(),
-                place_str,
-                team.team_name.clone(),
-                team.seed_time.clone().unwrap_or_default(),
-                team.final_time.clone(),
-                team.dq_description.clone().unwrap_or_default(),
-            ];
-
-            for i in 0..4 {
-                if i < team.swimmers.len() {
-                    row.push(team.swimmers[i].name.clone());
-                    row.push(team.swimmers[i].year.clone());
-                } else {
-                    row.push(String::new());
-                    row.push(String::new());
-                }
-            }
+/// Writes the summary header and one row per event
+fn write_summary_rows(
+    rows: impl IntoIterator<Item = [String; 8]>,
+    options: &OutputOptions,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv_writer(options).from_path(path)?;
+    writer.write_record(MEET_SUMMARY_HEADER)?;
 
-            for i in 0..4 {
-                if i < team.swimmers.len() {
-                    row.push(team.swimmers[i].reaction_time.clone().unwrap_or_default());
-                } else {
-                    row.push(String::new());
-                }
-            }
+    for row in rows {
+        writer.write_record(row)?;
+    }
 
-            for i in 0..max_splits {
-                if i < team.splits.len() {
-                    row.push(team.splits[i].time.clone());
-                } else {
-                    row.push(String::new());
-                }
-            }
+    writer.flush()?;
+    log::info!("Meet summary written to {}", path.display());
+    Ok(())
+}
+
+/// Writes a one-page meet overview: per event, the winner, winning time, margin of
+/// victory over second place, and whether the win set a record
+pub fn write_meet_summary_csv(
+    results: &ParsedResults,
+    options: &OutputOptions,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let rows = sorted_events(results).iter().map(|event| event_summary_row(event, options)).collect::<Vec<_>>();
+    write_summary_rows(rows, options, path)
+}
+
+// ============================================================================
+// EXCHANGE REPORT CSV OUTPUT
+// ============================================================================
 
-            writer.write_record(&row)?;
+/// Writes one row per relay exchange (legs 2-4) with a negative or close reaction (under
+/// CLOSE_EXCHANGE_LIMIT), across every relay in the meet, flagging early takeoffs past
+/// EARLY_TAKEOFF_LIMIT
+pub fn write_exchange_report_csv(
+    results: &ParsedResults,
+    options: &OutputOptions,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv_writer(options).from_path(path)?;
+    writer.write_record([
+        "event_number", "event_name", "session", "team_name", "place", "leg", "swimmer_name",
+        "reaction_time", "reaction_seconds", "early_takeoff", "dq_description",
+    ])?;
+
+    let mut events: Vec<&RelayResults> = results.relay_results.iter().collect();
+    events.sort_by_key(|e| (e.race_info.as_ref().map(|i| i.event_number), e.session));
+
+    for event in events {
+        let event_number = event.race_info.as_ref().map(|i| i.event_number.to_string()).unwrap_or_default();
+        for team in event.teams.iter().filter(|t| matches_team(&t.team_name, options)) {
+            for (i, swimmer) in team.legs().iter().enumerate().skip(1) {
+                let Some(reaction) = swimmer.reaction_seconds().filter(|&r| r < CLOSE_EXCHANGE_LIMIT) else {
+                    continue;
+                };
+                writer.write_record([
+                    event_number.clone(),
+                    event.event_name.clone(),
+                    session_label(event.session).to_string(),
+                    team.team_name.clone(),
+                    team.place.map(|p| p.to_string()).unwrap_or_default(),
+                    (i + 1).to_string(),
+                    options.name_format.apply(&swimmer.name),
+                    swimmer.reaction_time.clone().unwrap_or_default(),
+                    format!("{:.2}", reaction),
+                    (reaction < EARLY_TAKEOFF_LIMIT).to_string(),
+                    team.dq_description.clone().unwrap_or_default(),
+                ])?;
+            }
         }
     }
 
     writer.flush()?;
+    log::info!("Exchange report written to {}", path.display());
     Ok(())
 }
 
-/// Writes metadata to a specific file path
-fn write_metadata_csv_to_file(
-    individual_results: &[&EventResults],
-    relay_results: &[&RelayResults],
-    path: &PathBuf,
-) -> Result<(), Box<dyn Error>> {
-    let file = File::create(path)?;
-    let mut writer = csv::Writer::from_writer(file);
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::metadata::parse_race_info;
+    use crate::relay_handler::RelaySwimmer;
+
+    fn swimmer(place: Option<u8>, name: &str, school: &str, final_time: &str) -> Swimmer {
+        Swimmer {
+            place,
+            tied: false,
+            name: name.to_string(),
+            year: "SR".to_string(),
+            school: school.to_string(),
+            seed_time: Some("4:40.00".to_string()),
+            final_time: final_time.to_string(),
+            reaction_time: Some("0.65".to_string()),
+            dq_description: None,
+            standards: Vec::new(),
+            classification: None,
+            final_type: None,
+            splits: vec![Split::new(50, "26.10"), Split::new(100, "54.20")],
+            prelim_splits: Vec::new(),
+        }
+    }
 
-    writer.write_record(["event_name", "session", "venue", "meet_name", "records"])?;
+    fn individual_event(headline: &str, session: char, swimmers: Vec<Swimmer>) -> EventResults {
+        EventResults {
+            event_name: headline.to_string(),
+            session,
+            metadata: None,
+            race_info: parse_race_info(headline),
+            swimmers,
+            content_hash: String::new(),
+            raw_text: None,
+            leadoff: false,
+            warnings: Vec::new(),
+        }
+    }
 
-    for event in individual_results {
-        let session = if event.session == 'P' { "Prelims" } else { "Finals" };
-        let (venue, meet_name, records) = if let Some(ref meta) = event.metadata {
-            (
-                meta.venue.clone().unwrap_or_default(),
-                meta.meet_name.clone().unwrap_or_default(),
-                meta.records.iter()
-                    .map(|r| r.trim_matches('=').trim())
-                    .collect::<Vec<_>>()
-                    .join(" | "),
-            )
-        } else {
-            (String::new(), String::new(), String::new())
-        };
+    fn relay_team(place: Option<u8>, team_name: &str, final_time: &str) -> RelayTeam {
+        let swimmers = ["Smith, Jane", "Jones, Amy", "Brown, Kate", "White, Ana"].iter()
+            .map(|name| RelaySwimmer { name: name.to_string(), year: "SR".to_string(), ..RelaySwimmer::default() })
+            .collect();
+        RelayTeam {
+            place,
+            tied: false,
+            team_name: team_name.to_string(),
+            relay_designation: Some('A'),
+            seed_time: Some("1:34.00".to_string()),
+            final_time: final_time.to_string(),
+            dq_description: None,
+            swimmers,
+            splits: vec![Split::new(50, "22.10"), Split::new(100, "46.30")],
+        }
+    }
 
-        writer.write_record([
-            &event.event_name,
+    fn relay_event(headline: &str, session: char, teams: Vec<RelayTeam>) -> RelayResults {
+        RelayResults {
+            event_name: headline.to_string(),
             session,
-            &venue,
-            &meet_name,
-            &records,
-        ])?;
+            metadata: None,
+            race_info: parse_race_info(headline),
+            teams,
+            content_hash: String::new(),
+            raw_text: None,
+            warnings: Vec::new(),
+        }
     }
 
-    for event in relay_results {
-        let session = if event.session == 'P' { "Prelims" } else { "Finals" };
-        let (venue, meet_name, records) = if let Some(ref meta) = event.metadata {
-            (
-                meta.venue.clone().unwrap_or_default(),
-                meta.meet_name.clone().unwrap_or_default(),
-                meta.records.iter()
-                    .map(|r| r.trim_matches('=').trim())
-                    .collect::<Vec<_>>()
-                    .join(" | "),
-            )
-        } else {
-            (String::new(), String::new(), String::new())
+    /// Small meet with one individual and one relay event, shared with other modules' tests
+    pub(crate) fn sample_results() -> ParsedResults {
+        ParsedResults {
+            individual_results: vec![individual_event(
+                "Event 3  Women 500 Yard Freestyle",
+                'F',
+                vec![
+                    swimmer(Some(1), "Smith, Jane", "Stanford", "4:35.10"),
+                    swimmer(Some(2), "Jones, Amy", "Texas", "4:37.00"),
+                ],
+            )],
+            relay_results: vec![relay_event(
+                "Event 1  Women 200 Yard Medley Relay",
+                'F',
+                vec![relay_team(Some(1), "Stanford", "1:33.50")],
+            )],
+            meet_title: Some("Big Ten Championships".to_string()),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Empty directory under the system temp dir, unique to this test and process
+    pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rrs_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn deterministic_options(dir: &Path, on_conflict: OnConflict) -> OutputOptions {
+        OutputOptions {
+            naming: FolderNaming::Deterministic,
+            output_dir: Some(dir.to_path_buf()),
+            on_conflict,
+            ..OutputOptions::default()
+        }
+    }
+
+    #[test]
+    fn deterministic_rerun_overwrites_meet_folder() {
+        let dir = scratch_dir("deterministic_overwrite");
+        let options = deterministic_options(&dir, OnConflict::Overwrite);
+        let events = vec![individual_event(
+            "Event 3  Women 500 Yard Freestyle",
+            'F',
+            vec![swimmer(Some(1), "Smith, Jane", "Stanford", "4:35.10")],
+        )];
+
+        let first = write_results_to_folders(&events, &[], Some("Big Ten Championships"), None, &options).unwrap();
+        let second = write_results_to_folders(&events, &[], Some("Big Ten Championships"), None, &options).unwrap();
+
+        assert_eq!(first.meet_path, dir.join("Big_Ten_Championships"));
+        assert_eq!(second.meet_path, first.meet_path);
+        assert_eq!(second.scraped_at, None);
+        assert_eq!(second.events[0].folder, "E03_F_Women_500_Yard_Freestyle");
+        assert!(first.meet_path.join("E03_F_Women_500_Yard_Freestyle").join("results.csv").is_file());
+        assert!(!dir.join("Big_Ten_Championships-1").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn deterministic_rerun_suffixes_existing_meet_folder() {
+        let dir = scratch_dir("deterministic_suffix");
+        let options = deterministic_options(&dir, OnConflict::Suffix);
+        let events = vec![individual_event(
+            "Event 3  Women 500 Yard Freestyle",
+            'F',
+            vec![swimmer(Some(1), "Smith, Jane", "Stanford", "4:35.10")],
+        )];
+
+        let first = write_results_to_folders(&events, &[], Some("Big Ten Championships"), None, &options).unwrap();
+        let second = write_results_to_folders(&events, &[], Some("Big Ten Championships"), None, &options).unwrap();
+        let third = write_results_to_folders(&events, &[], Some("Big Ten Championships"), None, &options).unwrap();
+
+        assert_eq!(first.meet_path, dir.join("Big_Ten_Championships"));
+        assert_eq!(second.meet_path, dir.join("Big_Ten_Championships-1"));
+        assert_eq!(third.meet_path, dir.join("Big_Ten_Championships-2"));
+        assert!(second.meet_path.join("E03_F_Women_500_Yard_Freestyle").join("results.csv").is_file());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn json_outputs_include_splits() {
+        let results = sample_results();
+        let options = OutputOptions::default();
+
+        let mut json = Vec::new();
+        write_results_json_to(&results, &options, &mut json).unwrap();
+        let document: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let swimmer_splits = &document["individual_results"][0]["swimmers"][0]["splits"];
+        let team_splits = &document["relay_results"][0]["teams"][0]["splits"];
+        assert_eq!(swimmer_splits[1]["distance"], 100);
+        assert_eq!(swimmer_splits[1]["time"], "54.20");
+        assert_eq!(team_splits[0]["distance"], 50);
+        assert_eq!(team_splits[0]["time"], "22.10");
+
+        let mut jsonl = Vec::new();
+        write_results_jsonl(&results, &options, &mut jsonl).unwrap();
+        let records: Vec<serde_json::Value> = String::from_utf8(jsonl).unwrap().lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["splits"][0]["distance"], 50);
+        assert_eq!(records[0]["splits"][0]["time"], "26.10");
+        assert_eq!(records[2]["record_type"], "relay");
+        assert_eq!(records[2]["splits"][1]["time"], "46.30");
+
+        let mut ndjson = Vec::new();
+        write_results_ndjson(&results.individual_results, &results.relay_results, &mut ndjson).unwrap();
+        let events: Vec<serde_json::Value> = String::from_utf8(ndjson).unwrap().lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events[0]["swimmers"][1]["splits"][1]["distance"], 100);
+        assert_eq!(events[1]["teams"][0]["splits"][1]["distance"], 100);
+        assert_eq!(events[1]["teams"][0]["splits"][1]["time"], "46.30");
+    }
+
+    #[test]
+    fn json_schema_matches_fixture() {
+        let mut json = Vec::new();
+        write_results_json_to(&sample_results(), &OutputOptions::default(), &mut json).unwrap();
+        let actual: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(include_str!("../tests/fixtures/results.json")).unwrap();
+        assert_eq!(actual, expected, "JSON output changed; update tests/fixtures/results.json if intended");
+    }
+
+    /// Header row of the individual and relay CSVs written for the sample meet
+    fn csv_headers(options: &OutputOptions) -> (String, String) {
+        let results = sample_results();
+        let individual: Vec<&EventResults> = results.individual_results.iter().collect();
+        let relay: Vec<&RelayResults> = results.relay_results.iter().collect();
+        let first_line = |bytes: Vec<u8>| String::from_utf8(bytes).unwrap().lines().next().unwrap().to_string();
+
+        let mut buffer = Vec::new();
+        write_individual_csv_to(&individual, options, &mut buffer).unwrap();
+        let individual_header = first_line(buffer);
+        let mut buffer = Vec::new();
+        write_relay_csv_to(&relay, options, &mut buffer).unwrap();
+        (individual_header, first_line(buffer))
+    }
+
+    #[test]
+    fn no_splits_and_no_reaction_drop_only_their_columns() {
+        let (individual, relay) = csv_headers(&OutputOptions { include_splits: false, ..OutputOptions::default() });
+        assert_eq!(individual, "event_name,session,event_number,gender,distance,course,stroke,place,name,year,school,\
+seed_time,final_time,final_seconds,reaction_time,dq_description,final_type,leadoff");
+        assert_eq!(relay, "event_name,session,event_number,gender,distance,course,stroke,place,team_name,designation,\
+seed_time,final_time,final_seconds,dq_description,swimmer1_name,swimmer1_year,swimmer2_name,swimmer2_year,\
+swimmer3_name,swimmer3_year,swimmer4_name,swimmer4_year,swimmer1_leg,swimmer2_leg,swimmer3_leg,swimmer4_leg,\
+swimmer1_gender,swimmer2_gender,swimmer3_gender,swimmer4_gender,alternate_names,\
+swimmer1_reaction,swimmer2_reaction,swimmer3_reaction,swimmer4_reaction");
+
+        let (individual, relay) = csv_headers(&OutputOptions { include_reaction: false, ..OutputOptions::default() });
+        assert_eq!(individual, "event_name,session,event_number,gender,distance,course,stroke,place,name,year,school,\
+seed_time,final_time,final_seconds,dq_description,final_type,leadoff,split1,split2");
+        assert_eq!(relay, "event_name,session,event_number,gender,distance,course,stroke,place,team_name,designation,\
+seed_time,final_time,final_seconds,dq_description,swimmer1_name,swimmer1_year,swimmer2_name,swimmer2_year,\
+swimmer3_name,swimmer3_year,swimmer4_name,swimmer4_year,swimmer1_leg,swimmer2_leg,swimmer3_leg,swimmer4_leg,\
+swimmer1_gender,swimmer2_gender,swimmer3_gender,swimmer4_gender,alternate_names,split1,split2");
+    }
+
+    #[test]
+    fn school_with_a_comma_round_trips_through_each_delimiter() {
+        let mut results = sample_results();
+        results.individual_results[0].swimmers[0].school = "California, Berkeley".to_string();
+        let individual: Vec<&EventResults> = results.individual_results.iter().collect();
+
+        for delimiter in [b',', b'\t'] {
+            let options = OutputOptions { delimiter, ..OutputOptions::default() };
+            let mut buffer = Vec::new();
+            write_individual_csv_to(&individual, &options, &mut buffer).unwrap();
+
+            let mut reader = csv::ReaderBuilder::new().delimiter(delimiter).from_reader(buffer.as_slice());
+            let headers = reader.headers().unwrap().clone();
+            let school = headers.iter().position(|h| h == "school").unwrap();
+            let name = headers.iter().position(|h| h == "name").unwrap();
+            let rows: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+            assert_eq!(rows.len(), 2);
+            assert_eq!(&rows[0][school], "California, Berkeley", "delimiter {:?}", delimiter as char);
+            assert_eq!(&rows[0][name], "Smith, Jane", "delimiter {:?}", delimiter as char);
+            assert!(rows.iter().all(|row| row.len() == headers.len()));
+        }
+    }
+
+    #[test]
+    fn time_formats_keep_record_flags() {
+        assert_eq!(TimeFormat::Seconds.apply("1:39.50N"), "99.50N");
+        assert_eq!(TimeFormat::Centiseconds.apply("1:39.50N"), "9950N");
+        assert_eq!(TimeFormat::Seconds.apply("1:02.31"), "62.31");
+        assert_eq!(TimeFormat::Raw.apply("1:39.50N"), "1:39.50N");
+        for status in ["DQ", "NS", "NT", ""] {
+            assert_eq!(TimeFormat::Seconds.apply(status), status);
+        }
+
+        let mut results = sample_results();
+        results.individual_results[0].swimmers[0].final_time = "4:35.10M".to_string();
+        results.relay_results[0].teams[0].final_time = "1:33.50N".to_string();
+        results.format_times(TimeFormat::Seconds);
+        assert_eq!(results.individual_results[0].swimmers[0].final_time, "275.10M");
+        assert_eq!(results.individual_results[0].swimmers[1].final_time, "277.00");
+        assert_eq!(results.relay_results[0].teams[0].final_time, "93.50N");
+    }
+
+    #[test]
+    fn csv_output_matches_golden_files() {
+        let results = sample_results();
+        let options = OutputOptions::default();
+        let individual: Vec<&EventResults> = results.individual_results.iter().collect();
+        let relay: Vec<&RelayResults> = results.relay_results.iter().collect();
+
+        let mut buffer = Vec::new();
+        write_individual_csv_to(&individual, &options, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), include_str!("../tests/fixtures/individual.csv"));
+
+        let mut buffer = Vec::new();
+        write_relay_csv_to(&relay, &options, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), include_str!("../tests/fixtures/relay.csv"));
+
+        // The file writers used by folder output produce the same bytes
+        let dir = scratch_dir("csv_golden");
+        let individual_path = dir.join("individual.csv");
+        let relay_path = dir.join("relay.csv");
+        write_individual_csv_to_file(&individual, &options, &individual_path).unwrap();
+        write_relay_csv_to_file(&relay, &options, &relay_path).unwrap();
+        assert_eq!(fs::read_to_string(&individual_path).unwrap(), include_str!("../tests/fixtures/individual.csv"));
+        assert_eq!(fs::read_to_string(&relay_path).unwrap(), include_str!("../tests/fixtures/relay.csv"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Places 1-10 with two DQs, one listed mid-page as on some results pages
+    fn ten_places_two_dqs() -> EventResults {
+        let mut swimmers: Vec<Swimmer> = (1..=10u8)
+            .map(|place| swimmer(Some(place), &format!("Swimmer, No{}", place), "Stanford", "4:40.00"))
+            .collect();
+        swimmers.insert(5, swimmer(None, "Green, Lia", "Virginia", "DQ"));
+        swimmers.push(swimmer(None, "Black, Eve", "Ohio State", "DQ"));
+        individual_event("Event 3  Women 500 Yard Freestyle", 'F', swimmers)
+    }
+
+    #[test]
+    fn top_n_keeps_top_places_and_optionally_unplaced() {
+        let event = ten_places_two_dqs();
+        let names = |options: &OutputOptions| -> Vec<String> {
+            output_swimmers(&event, options).iter().map(|s| s.name.clone()).collect()
         };
 
-        writer.write_record([
-            &event.event_name,
-            session,
-            &venue,
-            &meet_name,
-            &records,
-        ])?;
+        let top3 = OutputOptions { top_n: Some(3), ..OutputOptions::default() };
+        assert_eq!(names(&top3), vec!["Swimmer, No1", "Swimmer, No2", "Swimmer, No3"]);
+        assert!(within_top_n(Some(3), &top3));
+        assert!(!within_top_n(Some(4), &top3));
+        assert!(!within_top_n(None, &top3));
+
+        let with_unplaced = OutputOptions { top_n: Some(3), include_unplaced: true, ..OutputOptions::default() };
+        assert_eq!(
+            names(&with_unplaced),
+            vec!["Swimmer, No1", "Swimmer, No2", "Swimmer, No3", "Green, Lia", "Black, Eve"],
+        );
+        assert!(within_top_n(None, &with_unplaced));
+
+        assert_eq!(names(&OutputOptions::default()).len(), 12);
     }
 
-    writer.flush()?;
-    Ok(())
+    #[test]
+    fn individual_and_relay_files_share_an_event_folder() {
+        let dir = scratch_dir("shared_event_folder");
+        let headline = "Event 5  Women 200 Yard Freestyle";
+        let individual = vec![individual_event(headline, 'F', vec![swimmer(Some(1), "Smith, Jane", "Stanford", "1:56.10")])];
+        let relay = vec![relay_event(headline, 'F', vec![relay_team(Some(1), "Texas", "1:33.50")])];
+        let options = OutputOptions { metadata: false, ..deterministic_options(&dir, OnConflict::Overwrite) };
+
+        let rows = |path: &Path| -> Vec<String> {
+            fs::read_to_string(path).unwrap().lines().map(str::to_string).collect()
+        };
+
+        let manifest = write_results_to_folders(&individual, &relay, Some("Dual Meet"), None, &options).unwrap();
+        let event_path = manifest.meet_path.join("E05_F_Women_200_Yard_Freestyle");
+        let individual_path = event_path.join("results.csv");
+        let relay_path = event_path.join("relay_results.csv");
+        assert_eq!(rows(&individual_path).len(), 2);
+        assert!(rows(&individual_path)[1].contains("Smith, Jane"));
+        assert_eq!(rows(&relay_path).len(), 2);
+        assert!(rows(&relay_path)[1].contains("Texas"));
+
+        let append = OutputOptions { append: true, ..options };
+        write_results_to_folders(&individual, &relay, Some("Dual Meet"), None, &append).unwrap();
+        assert_eq!(rows(&individual_path).len(), 3);
+        assert!(rows(&individual_path)[2].contains("Smith, Jane"));
+        assert_eq!(rows(&relay_path).len(), 3);
+        assert!(rows(&relay_path)[2].contains("Texas"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn folder_writer_finish_writes_summary_across_writes() {
+        let dir = scratch_dir("folder_writer_summary");
+        let results = sample_results();
+        let options = deterministic_options(&dir, OnConflict::Overwrite);
+
+        // Streamed out of order: event 3 arrives before event 1
+        let mut writer = FolderWriter::new(results.meet_title.as_deref(), &options).unwrap();
+        writer.write(&results.individual_results, &[]).unwrap();
+        writer.write(&[], &results.relay_results).unwrap();
+        let manifest = writer.finish().unwrap();
+
+        let summary = fs::read_to_string(manifest.meet_path.join(data_file(MEET_SUMMARY_CSV_FILE, &options))).unwrap();
+        let rows: Vec<&str> = summary.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[1].contains("Medley Relay"));
+        assert!(rows[2].contains("500 Yard Freestyle"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,58 @@
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+// ============================================================================
+// DATA STRUCTURES
+// ============================================================================
+
+/// Progress notification sent while a meet is processed
+#[derive(Debug, Clone)]
+pub enum ProgressEvent {
+    /// Meet index parsed; `total` event pages will be fetched
+    Started { total: usize },
+    /// Event page downloaded, about to be parsed
+    EventFetched { name: String, session: char },
+    /// Event page parsed successfully
+    EventParsed { name: String, session: char },
+    /// Event page unchanged since the last fetch (only-changed polling)
+    EventUnchanged { name: String, session: char },
+    /// Event page could not be fetched or parsed
+    EventFailed { name: String, session: char, error: String },
+}
+
+/// Shared progress callback; clones call the same function
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(ProgressEvent) + Send + Sync>);
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ProgressCallback")
+    }
+}
+
+// ============================================================================
+// REPORTING
+// ============================================================================
+
+impl ProgressCallback {
+    /// Wraps a function to be called for every progress event
+    pub fn new(callback: impl Fn(ProgressEvent) + Send + Sync + 'static) -> ProgressCallback {
+        ProgressCallback(Arc::new(callback))
+    }
+
+    /// Calls the callback, logging instead of propagating a panic so a faulty
+    /// callback cannot abort the scrape
+    pub fn report(&self, event: ProgressEvent) {
+        if panic::catch_unwind(AssertUnwindSafe(|| (self.0)(event))).is_err() {
+            log::error!("Progress callback panicked");
+        }
+    }
+}
+
+/// Reports an event if a callback is set
+pub(crate) fn report(callback: Option<&ProgressCallback>, event: ProgressEvent) {
+    if let Some(callback) = callback {
+        callback.report(event);
+    }
+}
@@ -1,44 +1,274 @@
 use scraper::{Html, Selector};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 
-use crate::utils::{fetch_html, is_dq_status, is_year_pattern, is_valid_time_format};
-use crate::event_handler::Split;
+use crate::utils::{has_points_column, fetch_html, hash_content, is_dq_status, parse_reaction_seconds, take_dq_description, parse_place, is_year_pattern, cumulative_split_time, name_first_last, name_last_first};
+use crate::event_handler::{EventResults, Split, Swimmer};
 use crate::metadata::{EventMetadata, RaceInfo, parse_event_metadata, parse_race_info};
+use crate::time::SwimTime;
+
+/// Number of swimmers (legs) in a relay
+pub const RELAY_LEGS: usize = 4;
+
+/// Exchanges faster than this (seconds) are early takeoffs and can be disqualified
+pub const EARLY_TAKEOFF_LIMIT: f64 = -0.03;
+
+/// Exchanges under this (seconds) are close enough to report
+pub const CLOSE_EXCHANGE_LIMIT: f64 = 0.10;
+
+/// Most swimmers listed for a relay entry: four legs plus two alternates, as on prelims pages
+pub const MAX_RELAY_SWIMMERS: usize = 6;
 
 // ============================================================================
 // DATA STRUCTURES
 // ============================================================================
 
 /// Individual swimmer within a relay team
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct RelaySwimmer {
     pub name: String,
+    /// Class year or age, or a nation code at international meets
     pub year: String,
     pub reaction_time: Option<String>,
+    /// Elapsed time of this swimmer's leg, from the team's cumulative splits
+    #[serde(default)]
+    pub leg_time: Option<String>,
+    /// Leg gender annotation ('M', 'W', or 'F'), read only for mixed relays
+    #[serde(default)]
+    pub gender: Option<char>,
 }
 
 /// Relay team result
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RelayTeam {
     pub place: Option<u8>,
+    /// Place is shared with another team
+    #[serde(default)]
+    pub tied: bool,
+    /// Team name without its relay designation
     pub team_name: String,
+    /// Which of a school's relays this is ('A', 'B', ...), from `Texas 'A'` or `Texas A`
+    #[serde(default)]
+    pub relay_designation: Option<char>,
     pub seed_time: Option<String>,
     pub final_time: String,
     pub dq_description: Option<String>,
+    /// Swimmers by leg; any past RELAY_LEGS are alternates
     pub swimmers: Vec<RelaySwimmer>,
-    #[serde(skip)]
     pub splits: Vec<Split>,
 }
 
 /// Complete relay event results with metadata
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RelayResults {
     pub event_name: String,
     pub session: char,
     pub metadata: Option<EventMetadata>,
     pub race_info: Option<RaceInfo>,
     pub teams: Vec<RelayTeam>,
+    /// Hex SHA-256 of the results text, for detecting changed live results
+    #[serde(default)]
+    pub content_hash: String,
+    /// Raw results text the parser saw, kept only with ScrapeOptions::keep_raw
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_text: Option<String>,
+    /// Fallbacks the parser hit (skipped sections, missing fields), empty if it parsed cleanly
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl RelaySwimmer {
+    /// Returns the name as "First Last"
+    pub fn name_first_last(&self) -> String {
+        name_first_last(&self.name)
+    }
+
+    /// Returns the name as "Last, First"
+    pub fn name_last_first(&self) -> String {
+        name_last_first(&self.name)
+    }
+
+    /// Returns the reaction time in seconds, keeping the sign
+    pub fn reaction_seconds(&self) -> Option<f64> {
+        parse_reaction_seconds(self.reaction_time.as_deref()?)
+    }
+
+    /// Flags a negative exchange reaction (the swimmer left before the incoming swimmer
+    /// touched). Only meaningful for legs 2-4; leg 1's reaction is off the start.
+    pub fn exchange_flag(&self) -> bool {
+        self.reaction_seconds().is_some_and(|r| r < 0.0)
+    }
+}
+
+impl RelayTeam {
+    /// Returns the final time as a SwimTime (None for DQ/NS entries)
+    pub fn final_time_parsed(&self) -> Option<SwimTime> {
+        self.final_time.parse().ok()
+    }
+
+    /// Returns the seed time as a SwimTime (None if missing or NT)
+    pub fn seed_time_parsed(&self) -> Option<SwimTime> {
+        self.seed_time.as_ref()?.parse().ok()
+    }
+
+    /// Returns the swimmers who swam the legs, without alternates
+    pub fn legs(&self) -> &[RelaySwimmer] {
+        &self.swimmers[..self.swimmers.len().min(RELAY_LEGS)]
+    }
+
+    /// Returns the alternates listed after the four legs (prelims entries list up to two)
+    pub fn alternates(&self) -> &[RelaySwimmer] {
+        self.swimmers.get(RELAY_LEGS..).unwrap_or_default()
+    }
+
+    /// Returns each leg's time in centiseconds, taking the relay distance from the last
+    /// split; see leg_times_for_distance
+    pub fn leg_times(&self) -> Vec<Option<u32>> {
+        match self.splits.last() {
+            Some(last) => self.leg_times_for_distance(last.distance),
+            None => vec![None; RELAY_LEGS],
+        }
+    }
+
+    /// Returns each leg's time in centiseconds for a relay of `distance`, as the difference
+    /// between the cumulative splits at consecutive leg boundaries. Legs whose boundary
+    /// splits are missing or unparsable are None.
+    pub fn leg_times_for_distance(&self, distance: u16) -> Vec<Option<u32>> {
+        let leg_distance = distance / RELAY_LEGS as u16;
+        let cumulative_at = |boundary: u16| -> Option<SwimTime> {
+            if boundary == 0 {
+                return Some(SwimTime::from_centiseconds(0));
+            }
+            let split = self.splits.iter().find(|s| s.distance == boundary);
+            match split {
+                Some(split) => split.time.parse().ok(),
+                // The finish split is sometimes missing; the final time covers it
+                None if boundary == distance => self.final_time_parsed(),
+                None => None,
+            }
+        };
+
+        (0..RELAY_LEGS as u16)
+            .map(|leg| {
+                let start = cumulative_at(leg * leg_distance)?;
+                let end = cumulative_at((leg + 1) * leg_distance)?;
+                end.checked_sub(start).map(|t| t.centiseconds())
+            })
+            .collect()
+    }
+
+    /// Sets each swimmer's leg_time from the splits, for a relay of `distance` (taken from
+    /// the last split when None). Legs are equal in distance, so this holds for medley
+    /// relays too.
+    pub fn assign_leg_times(&mut self, distance: Option<u16>) {
+        let leg_times = match distance {
+            Some(distance) => self.leg_times_for_distance(distance),
+            None => self.leg_times(),
+        };
+        for (swimmer, leg_time) in self.swimmers.iter_mut().zip(leg_times) {
+            swimmer.leg_time = leg_time.map(|c| SwimTime::from_centiseconds(c).to_string());
+        }
+    }
+
+    /// Returns the leadoff swimmer's first full leg (50 of a 200 relay, 100 of a 400), an
+    /// official time for individual rankings. Set from RaceInfo.distance when parsed; see
+    /// assign_leg_times.
+    pub fn leadoff_time(&self) -> Option<String> {
+        self.swimmers.first()?.leg_time.clone()
+    }
+}
+
+impl RelayResults {
+    /// Returns the relay distance from the headline, or else the longest final split
+    pub fn distance(&self) -> Option<u16> {
+        self.race_info.as_ref().and_then(|info| info.distance)
+            .or_else(|| self.teams.iter().filter_map(|t| t.splits.last()).map(|s| s.distance).max())
+    }
+
+    /// Builds one Swimmer per team from its leadoff leg: the first swimmer, with the team
+    /// as school and the cumulative split at the leg distance as the final time. Swims are
+    /// ordered and placed by time; teams without that split are left out.
+    pub fn leadoff_swims(&self) -> Vec<Swimmer> {
+        let Some(distance) = self.distance() else {
+            return Vec::new();
+        };
+        let leg_distance = distance / RELAY_LEGS as u16;
+
+        let mut swims: Vec<(u32, Swimmer)> = self.teams.iter()
+            .filter_map(|team| {
+                let leadoff = team.swimmers.first().filter(|s| !s.name.is_empty())?;
+                let time = team.leg_times_for_distance(distance).first().copied().flatten()?;
+                let swimmer = Swimmer {
+                    place: None,
+                    tied: false,
+                    name: leadoff.name.clone(),
+                    year: leadoff.year.clone(),
+                    school: team.team_name.clone(),
+                    seed_time: None,
+                    final_time: SwimTime::from_centiseconds(time).to_string(),
+                    reaction_time: leadoff.reaction_time.clone(),
+                    dq_description: None,
+                    standards: Vec::new(),
+                    classification: None,
+                    final_type: None,
+                    splits: team.splits.iter().filter(|s| s.distance <= leg_distance).cloned().collect(),
+                    prelim_splits: Vec::new(),
+                };
+                Some((time, swimmer))
+            })
+            .collect();
+        swims.sort_by_key(|(time, _)| *time);
+
+        // Equal times share a place
+        let times: Vec<u32> = swims.iter().map(|(time, _)| *time).collect();
+        for (i, (time, swimmer)) in swims.iter_mut().enumerate() {
+            let time = *time;
+            let first = times.iter().position(|&t| t == time).unwrap_or(i);
+            swimmer.place = u8::try_from(first + 1).ok();
+            swimmer.tied = times.iter().filter(|&&t| t == time).count() > 1;
+        }
+        swims.into_iter().map(|(_, swimmer)| swimmer).collect()
+    }
+
+    /// Returns the leadoff swims as an individual event named after the relay, e.g.
+    /// "Women 400 Yard Freestyle Relay Leadoff (100 Freestyle)"; None without any
+    pub fn leadoff_event(&self) -> Option<EventResults> {
+        let swimmers = self.leadoff_swims();
+        if swimmers.is_empty() {
+            return None;
+        }
+        let leg_distance = self.distance()? / RELAY_LEGS as u16;
+
+        // Medley relays lead off with backstroke
+        let relay_stroke = self.race_info.as_ref().and_then(|info| info.stroke.clone()).unwrap_or_default();
+        let stroke = if relay_stroke.to_lowercase().contains("medley") {
+            "Backstroke".to_string()
+        } else {
+            match relay_stroke.replace("Relay", "").trim() {
+                "" => "Freestyle".to_string(),
+                stroke => stroke.to_string(),
+            }
+        };
+
+        let race_info = self.race_info.clone().map(|info| RaceInfo {
+            distance: Some(leg_distance),
+            stroke: Some(stroke.clone()),
+            is_relay: false,
+            ..info
+        });
+
+        Some(EventResults {
+            event_name: format!("{} Leadoff ({} {})", self.event_name.trim(), leg_distance, stroke),
+            session: self.session,
+            metadata: self.metadata.clone(),
+            race_info,
+            swimmers,
+            content_hash: self.content_hash.clone(),
+            raw_text: None,
+            leadoff: true,
+            warnings: Vec::new(),
+        })
+    }
 }
 
 // ============================================================================
@@ -66,10 +296,19 @@ pub fn parse_relay_event_html(
 ) -> Result<RelayResults, Box<dyn Error>> {
     let document = Html::parse_document(html);
     let mut teams = Vec::new();
+    let distance = race_info.as_ref().and_then(|info| info.distance);
+    // Only mixed relays annotate leg genders; elsewhere a lone M or W is a middle initial
+    let mixed = race_info.as_ref()
+        .and_then(|info| info.gender.as_deref())
+        .is_some_and(|gender| gender.eq_ignore_ascii_case("Mixed"));
+
+    let mut content_hash = String::new();
+    let mut warnings = Vec::new();
 
     let pre_selector = Selector::parse("pre").unwrap();
     if let Some(pre) = document.select(&pre_selector).next() {
         let content = pre.text().collect::<String>();
+        content_hash = hash_content(&content);
         let lines: Vec<&str> = content.lines().collect();
 
         let mut i = 0;
@@ -87,8 +326,16 @@ pub fn parse_relay_event_html(
                     next_idx += 1;
                 }
 
-                if let Some(team) = parse_relay_team_section(&lines[i..next_idx]) {
-                    teams.push(team);
+                match parse_relay_team_section(&lines[i..next_idx], mixed) {
+                    Some(mut team) => {
+                        let missing = RELAY_LEGS - team.legs().iter().filter(|s| !s.name.is_empty()).count();
+                        if missing > 0 && team.dq_description.is_none() {
+                            warnings.push(format!("{} relay swimmer(s) missing: {}", missing, team.team_name));
+                        }
+                        team.assign_leg_times(distance);
+                        teams.push(team);
+                    }
+                    None => warnings.push(format!("team section skipped: too few fields: {}", current_line)),
                 }
 
                 i = next_idx;
@@ -96,6 +343,14 @@ pub fn parse_relay_event_html(
             }
             i += 1;
         }
+
+        if teams.is_empty() {
+            warnings.push("no teams found".to_string());
+        } else if teams.iter().all(|t| t.splits.is_empty()) {
+            warnings.push("no splits found".to_string());
+        }
+    } else {
+        warnings.push("no results text found".to_string());
     }
 
     Ok(RelayResults {
@@ -104,6 +359,9 @@ pub fn parse_relay_event_html(
         metadata,
         race_info,
         teams,
+        content_hash,
+        raw_text: None,
+        warnings,
     })
 }
 
@@ -115,7 +373,7 @@ pub fn parse_relay_event_html(
 fn is_relay_team_line(line: &str) -> bool {
     match line.split_whitespace().next() {
         Some(token) => {
-            let is_place = token.chars().all(|c| c.is_ascii_digit());
+            let is_place = parse_place(token).is_some();
             let is_dq = token == "--";
             (is_place || is_dq) && !line.contains(") ")
         }
@@ -123,8 +381,19 @@ fn is_relay_team_line(line: &str) -> bool {
     }
 }
 
+/// Checks if a line continues a team's DQ description rather than starting the roster
+/// or splits
+fn is_dq_description_line(line: &str) -> bool {
+    !line.is_empty()
+        && !line.starts_with("1)")
+        && !line.starts_with("r:")
+        && !line.starts_with("r+")
+        && line.chars().any(|c| c.is_ascii_alphabetic())
+        && !line.contains(") ")
+}
+
 /// Parses a relay team section (main line + swimmers + splits) into a RelayTeam
-fn parse_relay_team_section(lines: &[&str]) -> Option<RelayTeam> {
+fn parse_relay_team_section(lines: &[&str], mixed: bool) -> Option<RelayTeam> {
     let main_line = lines[0].trim();
     let parts: Vec<&str> = main_line.split_whitespace().collect();
 
@@ -132,17 +401,19 @@ fn parse_relay_team_section(lines: &[&str]) -> Option<RelayTeam> {
         return None;
     }
 
-    let is_dq_entry = parts[0] == "--";
-    let place: Option<u8> = if is_dq_entry {
-        None
+    // A team that did not finish is unplaced even if the line carries a place
+    let is_dq_entry = parts[0] == "--" || parts.last() == Some(&"DNF");
+    let (place, tied) = if is_dq_entry {
+        (None, false)
     } else {
-        Some(parts[0].parse().ok()?)
+        let (place, tied) = parse_place(parts[0])?;
+        (Some(place), tied)
     };
 
     let last = parts.last()?;
 
     // Determine field positions based on entry type
-    let (final_time, seed_time, team_end) = if last.parse::<u8>().is_ok() {
+    let (final_time, seed_time, team_end) = if parts.len() >= 5 && has_points_column(&parts) {
         (parts[parts.len() - 2], Some(parts[parts.len() - 3].to_string()), parts.len() - 3)
     } else if is_dq_status(last) {
         let seed = if parts.len() > 3 {
@@ -160,28 +431,17 @@ fn parse_relay_team_section(lines: &[&str]) -> Option<RelayTeam> {
         (*last, seed, parts.len() - 2)
     };
 
-    let team_name = parts[1..team_end].join(" ");
-
-    // Check for DQ description on the next line
-    let dq_description = if is_dq_entry && lines.len() > 1 {
-        let next_line = lines[1].trim();
-        if !next_line.is_empty()
-            && !next_line.starts_with("1)")
-            && !next_line.starts_with("r:")
-            && !next_line.starts_with("r+")
-            && next_line.chars().any(|c| c.is_ascii_alphabetic())
-            && !next_line.contains(") ")
-        {
-            Some(next_line.to_string())
-        } else {
-            None
-        }
+    let (team_name, relay_designation) = split_relay_designation(&parts[1..team_end]);
+
+    // DQ descriptions follow the team line and may wrap onto further lines
+    let (dq_description, description_lines) = if is_dq_entry {
+        take_dq_description(&lines[1..], is_dq_description_line)
     } else {
-        None
+        (None, 0)
     };
 
-    let swimmer_start_idx = if dq_description.is_some() { 2 } else { 1 };
-    let mut swimmers = parse_relay_swimmers(&lines[swimmer_start_idx..]);
+    let swimmer_start_idx = 1 + description_lines;
+    let mut swimmers = parse_relay_swimmers(&lines[swimmer_start_idx..], mixed);
     let (first_swimmer_reaction, splits) = parse_relay_splits(&lines[swimmer_start_idx..]);
 
     if !swimmers.is_empty() {
@@ -190,7 +450,9 @@ fn parse_relay_team_section(lines: &[&str]) -> Option<RelayTeam> {
 
     Some(RelayTeam {
         place,
+        tied,
         team_name,
+        relay_designation,
         seed_time,
         final_time: final_time.to_string(),
         dq_description,
@@ -199,62 +461,87 @@ fn parse_relay_team_section(lines: &[&str]) -> Option<RelayTeam> {
     })
 }
 
-/// Extracts four swimmers from relay swimmer lines
-fn parse_relay_swimmers(lines: &[&str]) -> Vec<RelaySwimmer> {
-    let mut swimmers: Vec<RelaySwimmer> = vec![
-        RelaySwimmer { name: String::new(), year: String::new(), reaction_time: None },
-        RelaySwimmer { name: String::new(), year: String::new(), reaction_time: None },
-        RelaySwimmer { name: String::new(), year: String::new(), reaction_time: None },
-        RelaySwimmer { name: String::new(), year: String::new(), reaction_time: None },
-    ];
-
-    for line in lines {
-        let line = line.trim();
-
-        // Skip split lines (no alphabetic characters except 'r')
-        let has_name_chars = line.chars().any(|c| c.is_ascii_alphabetic() && c != 'r');
-        if !has_name_chars {
-            continue;
+/// Splits a trailing relay designation off the team name tokens. Only a quoted letter
+/// (`'A'`) or a standalone A-D token counts, so a club name ending in another single
+/// letter is kept whole.
+fn split_relay_designation(tokens: &[&str]) -> (String, Option<char>) {
+    if let Some((last, rest)) = tokens.split_last() {
+        if !rest.is_empty() {
+            let quoted = last.len() == 3 && last.starts_with('\'') && last.ends_with('\'');
+            let letter = if quoted { &last[1..2] } else { last };
+            let designation = letter.chars().next().filter(|c| {
+                letter.len() == 1 && if quoted { c.is_ascii_uppercase() } else { ('A'..='D').contains(c) }
+            });
+            if designation.is_some() {
+                return (rest.join(" "), designation);
+            }
         }
+    }
+    (tokens.join(" "), None)
+}
 
-        // Skip lines without swimmer markers
-        if !line.starts_with("1)") && !line.starts_with("2)")
-            && !line.starts_with("3)") && !line.starts_with("4)")
-        {
-            continue;
+/// Extracts the four legs, plus any alternates listed after them, from relay swimmer lines
+fn parse_relay_swimmers(lines: &[&str], mixed: bool) -> Vec<RelaySwimmer> {
+    let mut swimmers: Vec<RelaySwimmer> = vec![RelaySwimmer::default(); RELAY_LEGS];
+
+    // Rosters wrap across any number of lines, so markers are found in the joined text.
+    // Split lines (no alphabetic characters except 'r') and lines without markers are skipped.
+    let roster = lines.iter()
+        .map(|line| line.trim())
+        .filter(|line| line.chars().any(|c| c.is_ascii_alphabetic() && c != 'r'))
+        .filter(|line| has_leg_marker(line))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut markers: Vec<(usize, usize)> = Vec::new();
+    let mut from = 0;
+    for swimmer_num in 1..=MAX_RELAY_SWIMMERS {
+        if let Some(pos) = find_leg_marker(&roster, swimmer_num, from) {
+            markers.push((swimmer_num, pos));
+            from = pos + 2;
         }
+    }
 
-        for swimmer_num in 1..=4 {
-            let marker = format!("{})", swimmer_num);
-            let search_pattern = format!("{}) ", swimmer_num);
-
-            if let Some(pos) = line.find(&search_pattern) {
-                if pos > 0 && !line[..pos].ends_with(char::is_whitespace) {
-                    continue;
-                }
-
-                let after_marker = &line[pos + marker.len()..];
-                let end_pos = (2..=4)
-                    .filter(|&n| n > swimmer_num)
-                    .filter_map(|n| after_marker.find(&format!("{}) ", n)))
-                    .min()
-                    .unwrap_or(after_marker.len());
-
-                let swimmer_text = after_marker[..end_pos].trim();
-
-                if let Some(swimmer) = parse_single_relay_swimmer(swimmer_text, swimmer_num) {
-                    swimmers[swimmer_num - 1] = swimmer;
-                }
+    for (i, &(swimmer_num, pos)) in markers.iter().enumerate() {
+        let end = markers.get(i + 1).map(|&(_, next)| next).unwrap_or(roster.len());
+        let swimmer_text = roster[pos + 2..end].trim();
+        if let Some(swimmer) = parse_single_relay_swimmer(swimmer_text, swimmer_num, mixed) {
+            if swimmers.len() < swimmer_num {
+                swimmers.resize(swimmer_num, RelaySwimmer::default());
             }
+            swimmers[swimmer_num - 1] = swimmer;
         }
     }
 
+    // Exhibition relays may list fewer than four swimmers; drop trailing empty legs
+    while swimmers.last().is_some_and(|s| s.name.is_empty()) {
+        swimmers.pop();
+    }
     swimmers
 }
 
-/// Parses a single swimmer's info (name, year, reaction time)
-fn parse_single_relay_swimmer(text: &str, swimmer_num: usize) -> Option<RelaySwimmer> {
-    let parts: Vec<&str> = text.split_whitespace().collect();
+/// Finds the "N)" leg marker at or after `from`, as its own token: at the start of the
+/// text or after whitespace, and followed by whitespace or the end
+/// Checks if a line holds any roster marker ("1)" through "6)"), anywhere on the line
+fn has_leg_marker(line: &str) -> bool {
+    (1..=MAX_RELAY_SWIMMERS).any(|n| find_leg_marker(line, n, 0).is_some())
+}
+
+fn find_leg_marker(text: &str, swimmer_num: usize, from: usize) -> Option<usize> {
+    let marker = format!("{})", swimmer_num);
+    text[from..].match_indices(&marker)
+        .map(|(pos, _)| from + pos)
+        .find(|&pos| {
+            let before_ok = text[..pos].chars().next_back().is_none_or(char::is_whitespace);
+            let after_ok = text[pos + marker.len()..].chars().next().is_none_or(char::is_whitespace);
+            before_ok && after_ok
+        })
+}
+
+/// Parses a single swimmer's info (name, year, reaction time, and for mixed relays the
+/// leg gender)
+fn parse_single_relay_swimmer(text: &str, swimmer_num: usize, mixed: bool) -> Option<RelaySwimmer> {
+    let mut parts: Vec<&str> = text.split_whitespace().collect();
     if parts.is_empty() {
         return None;
     }
@@ -272,14 +559,31 @@ fn parse_single_relay_swimmer(text: &str, swimmer_num: usize) -> Option<RelaySwi
         return None;
     }
 
-    // Find year position
+    // A mixed relay leg's gender sits next to the year ("Smith, Jane W SO" or "... SO W")
+    let mut gender = None;
+    if mixed {
+        let year_pos = parts.iter().skip(start_idx).position(|p| is_year_pattern(p) || is_age(p)).map(|i| i + start_idx);
+        let candidates = match year_pos {
+            Some(yi) => vec![yi + 1, yi.saturating_sub(1)],
+            None => vec![parts.len() - 1],
+        };
+        if let Some(i) = candidates.into_iter().find(|&i| i > start_idx && i < parts.len() && leg_gender(parts[i]).is_some()) {
+            gender = leg_gender(parts.remove(i));
+        }
+    }
+
+    // Find year position (class year or age), falling back to a trailing nation code
+    // as used by international meets
     let mut year_idx = None;
     for (i, &part) in parts.iter().enumerate().skip(start_idx) {
-        if is_year_pattern(part) {
+        if is_year_pattern(part) || is_age(part) {
             year_idx = Some(i);
             break;
         }
     }
+    if year_idx.is_none() && parts.len() - start_idx > 2 && is_nation_code(parts[parts.len() - 1]) {
+        year_idx = Some(parts.len() - 1);
+    }
 
     let (name, year) = if let Some(yi) = year_idx {
         (parts[start_idx..yi].join(" "), parts[yi].to_string())
@@ -291,9 +595,29 @@ fn parse_single_relay_swimmer(text: &str, swimmer_num: usize) -> Option<RelaySwi
         name,
         year,
         reaction_time,
+        leg_time: None,
+        gender,
     })
 }
 
+/// Reads a leg gender annotation ("M", "W", or "F")
+fn leg_gender(token: &str) -> Option<char> {
+    match token {
+        "M" | "W" | "F" => token.chars().next(),
+        _ => None,
+    }
+}
+
+/// Checks for a single-digit age (two-digit ages already match is_year_pattern)
+fn is_age(token: &str) -> bool {
+    token.len() == 1 && token.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Checks for a three-letter nation code like "AUS" or "USA"
+fn is_nation_code(token: &str) -> bool {
+    token.len() == 3 && token.chars().all(|c| c.is_ascii_uppercase())
+}
+
 /// Extracts first swimmer reaction time and split times from relay lines
 fn parse_relay_splits(lines: &[&str]) -> (Option<String>, Vec<Split>) {
     let mut splits = Vec::new();
@@ -305,10 +629,8 @@ fn parse_relay_splits(lines: &[&str]) -> (Option<String>, Vec<Split>) {
             continue;
         }
 
-        // Skip swimmer lines
-        if line.starts_with("1)") || line.starts_with("2)")
-            || line.starts_with("3)") || line.starts_with("4)")
-        {
+        // Skip roster lines, including alternates and rosters wrapped mid-swimmer
+        if has_leg_marker(line) {
             continue;
         }
 
@@ -324,15 +646,8 @@ fn parse_relay_splits(lines: &[&str]) -> (Option<String>, Vec<Split>) {
                 continue;
             }
 
-            let is_time = !part.contains('(')
-                && part.chars().next().is_some_and(|c| c.is_ascii_digit())
-                && is_valid_time_format(part);
-
-            if is_time {
-                splits.push(Split {
-                    distance: (splits.len() as u16 + 1) * 50,
-                    time: part.to_string(),
-                });
+            if let Some(time) = cumulative_split_time(part) {
+                splits.push(Split::new((splits.len() as u16 + 1) * 50, time));
             }
         }
     }
@@ -341,3 +656,236 @@ fn parse_relay_splits(lines: &[&str]) -> (Option<String>, Vec<Split>) {
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADLINE: &str = "Event 1  Women 200 Yard Freestyle Relay";
+
+    fn parse_page(body: &str) -> RelayResults {
+        let html = format!("<html><body><pre>{}</pre></body></html>", body);
+        parse_relay_event_html(&html, HEADLINE, 'F', None, parse_race_info(HEADLINE)).unwrap()
+    }
+
+    fn names_and_years(team: &RelayTeam) -> Vec<(&str, &str)> {
+        team.swimmers.iter().map(|s| (s.name.as_str(), s.year.as_str())).collect()
+    }
+
+    const ROSTER: [(&str, &str); 4] = [
+        ("Smith, Jane", "SR"),
+        ("Jones, Amy", "JR"),
+        ("Brown, Kate", "SO"),
+        ("White, Ana", "FR"),
+    ];
+
+    #[test]
+    fn roster_two_per_line() {
+        let results = parse_page("\
+  1 Stanford 'A'                    1:34.00    1:33.50
+     1) Smith, Jane SR              2) r:0.21 Jones, Amy JR
+     3) r:0.18 Brown, Kate SO       4) r:0.25 White, Ana FR
+     r:+0.62  22.10        46.30 (24.20)    1:10.50 (24.20)    1:33.50 (23.00)
+");
+        let team = &results.teams[0];
+        assert_eq!(names_and_years(team), ROSTER);
+        assert_eq!(team.swimmers[1].reaction_time.as_deref(), Some("r:0.21"));
+        assert_eq!(team.splits.len(), 4);
+    }
+
+    #[test]
+    fn roster_one_per_line() {
+        let results = parse_page("\
+  1 Stanford 'A'                    1:34.00    1:33.50
+     1) Smith, Jane SR
+     2) r:0.21 Jones, Amy JR
+     3) r:0.18 Brown, Kate SO
+     4) r:0.25 White, Ana FR
+     r:+0.62  22.10        46.30 (24.20)    1:10.50 (24.20)    1:33.50 (23.00)
+");
+        assert_eq!(names_and_years(&results.teams[0]), ROSTER);
+    }
+
+    #[test]
+    fn roster_three_then_one() {
+        let results = parse_page("\
+  1 Stanford 'A'                    1:34.00    1:33.50
+     1) Smith, Jane SR    2) r:0.21 Jones, Amy JR    3) r:0.18 Brown, Kate SO
+4) r:0.25 White, Ana FR
+     r:+0.62  22.10        46.30 (24.20)    1:10.50 (24.20)    1:33.50 (23.00)
+");
+        let team = &results.teams[0];
+        assert_eq!(names_and_years(team), ROSTER);
+        assert_eq!(team.swimmers[3].reaction_time.as_deref(), Some("r:0.25"));
+    }
+
+    #[test]
+    fn exhibition_roster_without_fourth_swimmer() {
+        let results = parse_page("\
+  1 Stanford 'B'                    1:36.00    1:35.50
+     1) Smith, Jane SR              2) r:0.21 Jones, Amy JR
+     3) r:0.18 Brown, Kate SO
+     r:+0.62  22.10        46.30 (24.20)    1:10.50 (24.20)    1:35.50 (25.00)
+");
+        let team = &results.teams[0];
+        assert_eq!(names_and_years(team), &ROSTER[..3]);
+        let json = serde_json::to_value(team).unwrap();
+        assert_eq!(json["swimmers"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn dnf_dq_and_dfs_teams_are_unplaced_with_notes() {
+        let results = parse_page("\
+  1 Stanford 'A'                    1:34.00    1:33.50
+     1) Smith, Jane SR              2) r:0.21 Jones, Amy JR
+     3) r:0.18 Brown, Kate SO       4) r:0.25 White, Ana FR
+     r:+0.62  22.10        46.30 (24.20)    1:10.50 (24.20)    1:33.50 (23.00)
+ -- Texas 'A'                       1:34.50         DQ
+     Early take-off swimmer #3
+     1) Green, Lia SR               2) r:0.20 Black, Eve JR
+     3) r:-0.05 Gray, Mia SO        4) r:0.22 Stone, Zoe FR
+  5 Cal 'A'                         1:35.00        DNF
+     Team did not finish
+ -- Virginia 'A'                    1:36.00        DFS
+     Declared false start
+");
+        let summary: Vec<(&str, Option<u8>, &str, Option<&str>)> = results.teams.iter()
+            .map(|t| (t.team_name.as_str(), t.place, t.final_time.as_str(), t.dq_description.as_deref()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("Stanford", Some(1), "1:33.50", None),
+            ("Texas", None, "DQ", Some("Early take-off swimmer #3")),
+            ("Cal", None, "DNF", Some("Team did not finish")),
+            ("Virginia", None, "DFS", Some("Declared false start")),
+        ]);
+        assert_eq!(results.teams[1].swimmers[0].name, "Green, Lia");
+
+        let top = crate::output::OutputOptions { top_n: Some(3), ..Default::default() };
+        let kept: Vec<&str> = results.teams.iter()
+            .filter(|t| crate::output::include_team(t, &top))
+            .map(|t| t.team_name.as_str())
+            .collect();
+        assert_eq!(kept, vec!["Stanford"]);
+    }
+
+    #[test]
+    fn international_roster_reads_ages_and_nation_codes() {
+        let results = parse_page("\
+  1 Australia                 AUS       3:30.05    3:29.80
+     1) McKeown, Kaylee 22          2) r:0.31 Harris, Jenna 19
+     3) r:0.25 McKeon, Emma 29      4) r:0.19 O'Callaghan, Mollie 20
+     r:+0.58  27.30        58.10 (30.80)    1:25.40 (27.30)    3:29.80 (24.40)
+  2 United States             USA       3:31.00    3:30.50
+     1) Smith, Regan USA            2) r:0.28 Huske, Torri USA
+     3) r:0.22 Walsh, Gretchen USA  4) r:0.20 Douglass, Kate USA
+     r:+0.61  27.50        58.40 (30.90)    1:25.80 (27.40)    3:30.50 (24.70)
+");
+        assert_eq!(names_and_years(&results.teams[0]), vec![
+            ("McKeown, Kaylee", "22"),
+            ("Harris, Jenna", "19"),
+            ("McKeon, Emma", "29"),
+            ("O'Callaghan, Mollie", "20"),
+        ]);
+        assert_eq!(names_and_years(&results.teams[1]), vec![
+            ("Smith, Regan", "USA"),
+            ("Huske, Torri", "USA"),
+            ("Walsh, Gretchen", "USA"),
+            ("Douglass, Kate", "USA"),
+        ]);
+        assert_eq!(results.teams[1].swimmers[2].reaction_time.as_deref(), Some("r:0.22"));
+    }
+
+    #[test]
+    fn team_name_ending_in_a_number_with_and_without_points() {
+        let results = parse_page("\
+  1 Club Wolverine 2 'A'            1:34.00    1:33.50   40
+     r:+0.62  22.10        46.30 (24.20)    1:10.50 (24.20)    1:33.50 (23.00)
+  2 Unattached 1 'A'                1:35.00    1:34.20
+     r:+0.65  22.30        46.70 (24.40)    1:10.90 (24.20)    1:34.20 (23.30)
+  3 Club Wolverine 2 'B'            1:36.00    1:35.10
+     r:+0.66  22.50        47.00 (24.50)    1:11.30 (24.30)    1:35.10 (23.80)
+");
+        let teams: Vec<(&str, Option<char>, Option<&str>, &str)> = results.teams.iter()
+            .map(|t| (t.team_name.as_str(), t.relay_designation, t.seed_time.as_deref(), t.final_time.as_str()))
+            .collect();
+        assert_eq!(teams, vec![
+            ("Club Wolverine 2", Some('A'), Some("1:34.00"), "1:33.50"),
+            ("Unattached 1", Some('A'), Some("1:35.00"), "1:34.20"),
+            ("Club Wolverine 2", Some('B'), Some("1:36.00"), "1:35.10"),
+        ]);
+    }
+
+    #[test]
+    fn florida_medley_relay_leadoff_is_the_backstroke_hundred() {
+        let headline = "Event 18  Men 400 Yard Medley Relay";
+        let html = "<html><body><pre>
+  1 Florida 'A'                     3:01.00    2:59.98
+     1) Chaney, Adam SR             2) r:0.24 Smith, Jake JR
+     3) r:0.18 Liendo, Josh SO      4) r:0.20 McDuff, Macguire JR
+     r:+0.55  21.82        44.80 (44.80)    1:06.30 (21.50)    1:36.90 (52.10)
+     1:56.10 (19.20)    2:19.40 (42.50)    2:38.30 (18.90)    2:59.98 (40.58)
+ -- Florida 'B'                     3:05.00         DQ
+     Early take-off swimmer #2
+</pre></body></html>";
+        let results = parse_relay_event_html(html, headline, 'F', None, parse_race_info(headline)).unwrap();
+
+        let team = &results.teams[0];
+        assert_eq!(team.splits.len(), 8);
+        assert_eq!(team.leadoff_time().as_deref(), Some("44.80"));
+        let legs: Vec<Option<&str>> = team.swimmers.iter().map(|s| s.leg_time.as_deref()).collect();
+        assert_eq!(legs, vec![Some("44.80"), Some("52.10"), Some("42.50"), Some("40.58")]);
+        assert_eq!(results.teams[1].leadoff_time(), None);
+
+        let leadoffs = results.leadoff_swims();
+        assert_eq!(leadoffs.len(), 1);
+        assert_eq!((leadoffs[0].name.as_str(), leadoffs[0].final_time.as_str()), ("Chaney, Adam", "44.80"));
+    }
+
+    #[test]
+    fn two_line_dq_reason_is_joined_before_the_roster() {
+        let results = parse_page("\
+ -- Texas 'A'                       1:34.50         DQ
+     Early take-off swimmer #3
+     Stroke infraction - butterfly kick
+     1) Green, Lia SR               2) r:0.20 Black, Eve JR
+     3) r:-0.05 Gray, Mia SO        4) r:0.22 Stone, Zoe FR
+     r:+0.64  22.30        46.60 (24.30)    1:10.40 (23.80)    1:34.50 (24.10)
+");
+        let team = &results.teams[0];
+        assert_eq!(team.place, None);
+        assert_eq!(
+            team.dq_description.as_deref(),
+            Some("Early take-off swimmer #3 Stroke infraction - butterfly kick"),
+        );
+        let names: Vec<&str> = team.swimmers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Green, Lia", "Black, Eve", "Gray, Mia", "Stone, Zoe"]);
+        assert_eq!(team.swimmers[2].reaction_time.as_deref(), Some("r:-0.05"));
+        assert_eq!(team.splits.len(), 4);
+    }
+
+    #[test]
+    fn alternates_and_wrapped_roster_lines_are_not_read_as_splits() {
+        let results = parse_page("\
+  1 Stanford 'A'                    1:34.00    1:33.50
+     1) Smith-Whitfield, Jane Elizabeth
+        SR   2) r:0.21 Jones, Amy JR
+     3) r:0.18 Brown, Kate SO       4) r:0.25 White, Ana FR
+     r:+0.62  22.10        46.30 (24.20)    1:10.50 (24.20)    1:33.50 (23.00)
+  2 Texas 'A'                       1:35.00    1:34.40
+     1) Green, Lia SR               2) r:0.22 Black, Mia JR
+     3) r:0.19 Gray, Eve SO         4) r:0.24 Stone, Ivy FR
+     5) r:0.30 Reed, Zoe FR         6) r:0.28 Hall, Ada SO
+     r:+0.66  22.40        46.80 (24.40)    1:10.90 (24.10)    1:34.40 (23.50)
+");
+        let stanford = &results.teams[0];
+        assert_eq!(stanford.swimmers[0].name, "Smith-Whitfield, Jane Elizabeth");
+        assert_eq!(stanford.swimmers[0].reaction_time.as_deref(), Some("r:+0.62"));
+        assert_eq!(stanford.splits.len(), 4);
+
+        let texas = &results.teams[1];
+        assert_eq!(texas.swimmers.len(), 6);
+        assert_eq!(texas.swimmers[0].reaction_time.as_deref(), Some("r:+0.66"));
+        let splits: Vec<(u16, &str)> = texas.splits.iter().map(|s| (s.distance, s.time.as_str())).collect();
+        assert_eq!(splits, vec![(50, "22.40"), (100, "46.80"), (150, "1:10.90"), (200, "1:34.40")]);
+    }
+}
@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+/// Product token matched against robots.txt User-agent lines
+pub const ROBOTS_USER_AGENT: &str = "realtime_results_scraper";
+
+// ============================================================================
+// DATA STRUCTURES
+// ============================================================================
+
+/// Rules from a host's robots.txt that apply to this crawler
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsRules {
+    pub allow: Vec<String>,
+    pub disallow: Vec<String>,
+    pub crawl_delay: Option<Duration>,
+}
+
+/// One User-agent group and its rules
+#[derive(Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: RobotsRules,
+}
+
+// ============================================================================
+// PARSING
+// ============================================================================
+
+impl RobotsRules {
+    /// Parses robots.txt, keeping the rules of groups naming this crawler or,
+    /// if none do, the rules of "*" groups
+    pub fn parse(text: &str) -> RobotsRules {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut in_agent_lines = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            if key == "user-agent" {
+                // Consecutive User-agent lines share one group
+                if !in_agent_lines {
+                    groups.push(Group::default());
+                }
+                if let Some(group) = groups.last_mut() {
+                    group.agents.push(value.to_lowercase());
+                }
+                in_agent_lines = true;
+                continue;
+            }
+            in_agent_lines = false;
+
+            let Some(group) = groups.last_mut() else {
+                continue;
+            };
+            match key.as_str() {
+                "allow" if !value.is_empty() => group.rules.allow.push(value.to_string()),
+                "disallow" if !value.is_empty() => group.rules.disallow.push(value.to_string()),
+                "crawl-delay" => {
+                    group.rules.crawl_delay = value.parse::<f64>().ok()
+                        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                        .map(Duration::from_secs_f64);
+                }
+                _ => {}
+            }
+        }
+
+        let named: Vec<&Group> = groups.iter()
+            .filter(|g| g.agents.iter().any(|a| a != "*" && ROBOTS_USER_AGENT.contains(a.as_str())))
+            .collect();
+        let matching = if named.is_empty() {
+            groups.iter().filter(|g| g.agents.iter().any(|a| a == "*")).collect()
+        } else {
+            named
+        };
+
+        let mut rules = RobotsRules::default();
+        for group in matching {
+            rules.allow.extend(group.rules.allow.iter().cloned());
+            rules.disallow.extend(group.rules.disallow.iter().cloned());
+            rules.crawl_delay = rules.crawl_delay.max(group.rules.crawl_delay);
+        }
+        rules
+    }
+
+    /// Checks if a URL path may be fetched; the longest matching rule wins, ties go to Allow
+    pub fn is_allowed(&self, path: &str) -> bool {
+        let longest = |patterns: &[String]| {
+            patterns.iter()
+                .filter(|p| pattern_matches(p, path))
+                .map(|p| p.len())
+                .max()
+        };
+
+        match (longest(&self.allow), longest(&self.disallow)) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(allow), Some(disallow)) => allow >= disallow,
+        }
+    }
+}
+
+/// Matches a robots.txt path pattern, supporting `*` wildcards and a trailing `$` anchor
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return true;
+    };
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut last_part = first;
+    for part in parts {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+        last_part = part;
+    }
+
+    if !anchored || rest.is_empty() {
+        return true;
+    }
+    // An anchored pattern may still match at a later occurrence of its final literal
+    pattern.contains('*') && rest.ends_with(last_part)
+}
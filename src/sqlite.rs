@@ -0,0 +1,247 @@
+use rusqlite::{params, Connection, Transaction};
+use std::error::Error;
+use std::path::Path;
+
+use crate::event_handler::{EventResults, Split};
+use crate::metadata::RaceInfo;
+use crate::relay_handler::RelayResults;
+use crate::ParsedResults;
+
+// ============================================================================
+// SCHEMA
+// ============================================================================
+
+/// Tables are created only if missing, so several meets can share one database
+const SCHEMA: &str = "
+PRAGMA foreign_keys = ON;
+
+CREATE TABLE IF NOT EXISTS meets (
+    id INTEGER PRIMARY KEY,
+    title TEXT
+);
+
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY,
+    meet_id INTEGER NOT NULL REFERENCES meets(id) ON DELETE CASCADE,
+    event_name TEXT NOT NULL,
+    event_number INTEGER,
+    session TEXT NOT NULL,
+    gender TEXT,
+    distance INTEGER,
+    course TEXT,
+    stroke TEXT,
+    is_relay INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS swimmers (
+    id INTEGER PRIMARY KEY,
+    event_id INTEGER NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    place INTEGER,
+    name TEXT NOT NULL,
+    year TEXT,
+    school TEXT,
+    seed_time TEXT,
+    final_time TEXT,
+    final_centiseconds INTEGER,
+    reaction_time TEXT,
+    dq_description TEXT,
+    final_type TEXT
+);
+
+CREATE TABLE IF NOT EXISTS relay_teams (
+    id INTEGER PRIMARY KEY,
+    event_id INTEGER NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    place INTEGER,
+    team_name TEXT NOT NULL,
+    seed_time TEXT,
+    final_time TEXT,
+    final_centiseconds INTEGER,
+    dq_description TEXT
+);
+
+CREATE TABLE IF NOT EXISTS relay_swimmers (
+    id INTEGER PRIMARY KEY,
+    relay_team_id INTEGER NOT NULL REFERENCES relay_teams(id) ON DELETE CASCADE,
+    leg INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    year TEXT,
+    reaction_time TEXT
+);
+
+CREATE TABLE IF NOT EXISTS splits (
+    id INTEGER PRIMARY KEY,
+    swimmer_id INTEGER REFERENCES swimmers(id) ON DELETE CASCADE,
+    relay_team_id INTEGER REFERENCES relay_teams(id) ON DELETE CASCADE,
+    distance INTEGER NOT NULL,
+    time TEXT NOT NULL
+);
+";
+
+// ============================================================================
+// SQLITE OUTPUT
+// ============================================================================
+
+/// Appends a meet's results to a SQLite database, creating the tables if needed
+pub fn write_results_sqlite(results: &ParsedResults, path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut conn = Connection::open(path)?;
+    conn.execute_batch(SCHEMA)?;
+
+    let tx = conn.transaction()?;
+    tx.execute("INSERT INTO meets (title) VALUES (?1)", params![results.meet_title])?;
+    let meet_id = tx.last_insert_rowid();
+
+    for event in &results.individual_results {
+        insert_individual_event(&tx, meet_id, event)?;
+    }
+    for event in &results.relay_results {
+        insert_relay_event(&tx, meet_id, event)?;
+    }
+
+    tx.commit()?;
+    log::info!("Results written to {}", path.display());
+    Ok(())
+}
+
+/// Inserts an events row and returns its id
+fn insert_event(
+    tx: &Transaction,
+    meet_id: i64,
+    event_name: &str,
+    session: char,
+    race_info: Option<&RaceInfo>,
+    is_relay: bool,
+) -> Result<i64, Box<dyn Error>> {
+    tx.execute(
+        "INSERT INTO events (meet_id, event_name, event_number, session, gender, distance, course, stroke, is_relay)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            meet_id,
+            event_name,
+            race_info.map(|i| i.event_number),
+            session.to_string(),
+            race_info.and_then(|i| i.gender.as_deref()),
+            race_info.and_then(|i| i.distance),
+            race_info.and_then(|i| i.course.as_deref()),
+            race_info.and_then(|i| i.stroke.as_deref()),
+            is_relay,
+        ],
+    )?;
+    Ok(tx.last_insert_rowid())
+}
+
+fn insert_individual_event(tx: &Transaction, meet_id: i64, event: &EventResults) -> Result<(), Box<dyn Error>> {
+    let event_id = insert_event(tx, meet_id, &event.event_name, event.session, event.race_info.as_ref(), false)?;
+
+    for swimmer in &event.swimmers {
+        tx.execute(
+            "INSERT INTO swimmers (event_id, place, name, year, school, seed_time, final_time,
+                                   final_centiseconds, reaction_time, dq_description, final_type)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                event_id,
+                swimmer.place,
+                swimmer.name,
+                swimmer.year,
+                swimmer.school,
+                swimmer.seed_time,
+                swimmer.final_time,
+                swimmer.final_time_parsed().map(|t| t.centiseconds()),
+                swimmer.reaction_time,
+                swimmer.dq_description,
+                swimmer.final_type.map(|t| t.to_string()),
+            ],
+        )?;
+        let swimmer_id = tx.last_insert_rowid();
+        insert_splits(tx, "swimmer_id", swimmer_id, &swimmer.splits)?;
+    }
+    Ok(())
+}
+
+fn insert_relay_event(tx: &Transaction, meet_id: i64, event: &RelayResults) -> Result<(), Box<dyn Error>> {
+    let event_id = insert_event(tx, meet_id, &event.event_name, event.session, event.race_info.as_ref(), true)?;
+
+    for team in &event.teams {
+        tx.execute(
+            "INSERT INTO relay_teams (event_id, place, team_name, seed_time, final_time,
+                                      final_centiseconds, dq_description)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                event_id,
+                team.place,
+                team.team_name,
+                team.seed_time,
+                team.final_time,
+                team.final_time_parsed().map(|t| t.centiseconds()),
+                team.dq_description,
+            ],
+        )?;
+        let team_id = tx.last_insert_rowid();
+
+        for (leg, swimmer) in team.swimmers.iter().enumerate() {
+            if swimmer.name.is_empty() {
+                continue;
+            }
+            tx.execute(
+                "INSERT INTO relay_swimmers (relay_team_id, leg, name, year, reaction_time)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![team_id, leg as i64 + 1, swimmer.name, swimmer.year, swimmer.reaction_time],
+            )?;
+        }
+        insert_splits(tx, "relay_team_id", team_id, &team.splits)?;
+    }
+    Ok(())
+}
+
+/// Inserts splits owned by a swimmer or relay team (`owner_column` names the foreign key)
+fn insert_splits(tx: &Transaction, owner_column: &str, owner_id: i64, splits: &[Split]) -> Result<(), Box<dyn Error>> {
+    let sql = format!("INSERT INTO splits ({}, distance, time) VALUES (?1, ?2, ?3)", owner_column);
+    let mut stmt = tx.prepare_cached(&sql)?;
+    for split in splits {
+        stmt.execute(params![owner_id, split.distance, split.time])?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::output::tests::{sample_results, scratch_dir};
+
+    #[test]
+    fn meets_share_one_database() {
+        let dir = scratch_dir("sqlite_two_meets");
+        let path = dir.join("results.db");
+        let results = sample_results();
+
+        // The schema is created only if missing, so a second write appends a second meet
+        write_results_sqlite(&results, &path).unwrap();
+        write_results_sqlite(&results, &path).unwrap();
+
+        let conn = Connection::open(&path).unwrap();
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM meets"), 2);
+        assert_eq!(count("SELECT COUNT(*) FROM events"), 4);
+        assert_eq!(count("SELECT COUNT(*) FROM swimmers"), 4);
+        assert_eq!(count("SELECT COUNT(*) FROM relay_swimmers"), 8);
+
+        let mut stmt = conn.prepare(
+            "SELECT e.event_name, s.name, sp.distance, sp.time
+             FROM events e
+             JOIN swimmers s ON s.event_id = e.id
+             JOIN splits sp ON sp.swimmer_id = s.id
+             WHERE e.meet_id = (SELECT MAX(id) FROM meets) AND s.place = 1
+             ORDER BY sp.distance",
+        ).unwrap();
+        let rows: Vec<(String, String, i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(rows, vec![
+            ("Event 3  Women 500 Yard Freestyle".to_string(), "Smith, Jane".to_string(), 50, "26.10".to_string()),
+            ("Event 3  Women 500 Yard Freestyle".to_string(), "Smith, Jane".to_string(), 100, "54.20".to_string()),
+        ]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
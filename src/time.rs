@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+// ============================================================================
+// DATA STRUCTURES
+// ============================================================================
+
+/// Swim time stored as centiseconds (e.g. 1:08.61 = 6861)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SwimTime(u32);
+
+/// Error returned when a string is not a valid swim time
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseSwimTimeError;
+
+impl fmt::Display for ParseSwimTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid swim time")
+    }
+}
+
+impl Error for ParseSwimTimeError {}
+
+// ============================================================================
+// SWIM TIME
+// ============================================================================
+
+impl SwimTime {
+    /// Creates a SwimTime from centiseconds
+    pub fn from_centiseconds(centiseconds: u32) -> SwimTime {
+        SwimTime(centiseconds)
+    }
+
+    /// Returns the time in centiseconds
+    pub fn centiseconds(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns the time in seconds
+    pub fn as_seconds(&self) -> f64 {
+        f64::from(self.0) / 100.0
+    }
+
+    /// Subtracts another time, returning None if the result would be negative
+    pub fn checked_sub(self, other: SwimTime) -> Option<SwimTime> {
+        self.0.checked_sub(other.0).map(SwimTime)
+    }
+}
+
+impl FromStr for SwimTime {
+    type Err = ParseSwimTimeError;
+
+    /// Parses `[H:]MM:SS.hh` or `SS.hh`, ignoring trailing flag letters (e.g. "4:02.31N")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let (whole, frac) = s.split_once('.').ok_or(ParseSwimTimeError)?;
+
+        if frac.len() != 2 || !frac.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ParseSwimTimeError);
+        }
+        let hundredths: u32 = frac.parse().map_err(|_| ParseSwimTimeError)?;
+
+        let fields: Vec<&str> = whole.split(':').collect();
+        if fields.len() > 3 {
+            return Err(ParseSwimTimeError);
+        }
+
+        let mut seconds: u32 = 0;
+        for (i, field) in fields.iter().enumerate() {
+            if field.is_empty() || !field.chars().all(|c| c.is_ascii_digit()) {
+                return Err(ParseSwimTimeError);
+            }
+            let value: u32 = field.parse().map_err(|_| ParseSwimTimeError)?;
+            // Fields after the first are zero-padded seconds/minutes
+            if i > 0 && (field.len() != 2 || value >= 60) {
+                return Err(ParseSwimTimeError);
+            }
+            seconds = seconds.checked_mul(60)
+                .and_then(|acc| acc.checked_add(value))
+                .ok_or(ParseSwimTimeError)?;
+        }
+
+        seconds.checked_mul(100)
+            .and_then(|c| c.checked_add(hundredths))
+            .map(SwimTime)
+            .ok_or(ParseSwimTimeError)
+    }
+}
+
+impl fmt::Display for SwimTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hundredths = self.0 % 100;
+        let total_seconds = self.0 / 100;
+        let hours = total_seconds / 3600;
+        let minutes = (total_seconds % 3600) / 60;
+        let seconds = total_seconds % 60;
+
+        if hours > 0 {
+            write!(f, "{}:{:02}:{:02}.{:02}", hours, minutes, seconds, hundredths)
+        } else if minutes > 0 {
+            write!(f, "{}:{:02}.{:02}", minutes, seconds, hundredths)
+        } else {
+            write!(f, "{}.{:02}", seconds, hundredths)
+        }
+    }
+}
@@ -1,33 +1,205 @@
 use std::error::Error;
 use chrono::Local;
+use encoding_rs::{Encoding, WINDOWS_1252};
+use scraper::{Html, Selector};
+use sha2::{Digest, Sha256};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::http_client::shared_client;
+use crate::time::SwimTime;
 
 /// Generates a unique ID using datetime
 pub fn generate_unique_id() -> String {
     Local::now().format("%Y%m%d_%H%M%S").to_string()
 }
 
+/// Longest sanitized name, in bytes, leaving room for ids and extensions within the
+/// usual 255-byte file name limit
+const MAX_NAME_BYTES: usize = 100;
+
+/// Characters that are unsafe in file names on common filesystems
+fn is_unsafe_filename_char(c: char) -> bool {
+    matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control()
+}
+
 /// Sanitizes a string for use as a folder/file name
+///
+/// Normalizes to NFC so differently encoded accents give the same name, keeps accented
+/// letters and punctuation, and replaces whitespace and filesystem-unsafe characters with
+/// single underscores. Names over MAX_NAME_BYTES are cut and given a short hash of the
+/// full name, so long titles sharing a prefix stay distinct.
 pub fn sanitize_name(name: &str) -> String {
-    name.chars()
-        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-' || *c == '_')
-        .collect::<String>()
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join("_")
+    let mut sanitized = String::with_capacity(name.len());
+    for c in name.nfc() {
+        let c = if c.is_whitespace() || is_unsafe_filename_char(c) { '_' } else { c };
+        if c == '_' && sanitized.ends_with('_') {
+            continue;
+        }
+        sanitized.push(c);
+    }
+    let sanitized = sanitized.trim_matches(|c| c == '_' || c == '.');
+
+    if sanitized.len() <= MAX_NAME_BYTES {
+        return sanitized.to_string();
+    }
+
+    let hash = hash_content(sanitized);
+    let keep = MAX_NAME_BYTES - 9;
+    let cut = (0..=keep).rev().find(|&i| sanitized.is_char_boundary(i)).unwrap_or(0);
+    format!("{}_{}", sanitized[..cut].trim_end_matches(['_', '.']), &hash[..8])
+}
+
+/// Returns the hex SHA-256 of page content, for cheaply detecting changed results
+pub fn hash_content(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Returns the text of the first `<pre>` block, where results pages keep their results
+pub fn pre_text(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let pre_selector = Selector::parse("pre").unwrap();
+    document.select(&pre_selector).next()
+        .map(|pre| pre.text().collect::<String>())
+}
+
+/// Reads the charset named after "charset=" (e.g. in "text/html; charset=iso-8859-1")
+fn charset_label(text: &str) -> Option<&'static Encoding> {
+    let lower = text.to_ascii_lowercase();
+    let start = lower.find("charset=")? + "charset=".len();
+    let label: String = lower[start..].chars()
+        .skip_while(|c| *c == '"' || *c == '\'')
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+        .collect();
+    Encoding::for_label(label.as_bytes())
 }
 
-/// Fetches HTML content from a URL
+/// Decodes page bytes using the Content-Type header's charset, then a `<meta>` charset
+/// declaration; undeclared pages are read as UTF-8, or Windows-1252 when that fails
+/// (older Hy-Tek pages), so accented names like "Müller" survive
+pub fn decode_html(bytes: &[u8], content_type: Option<&str>) -> String {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(2048)]);
+    let encoding = content_type.and_then(charset_label)
+        .or_else(|| charset_label(&head));
+
+    match encoding {
+        Some(encoding) => encoding.decode(bytes).0.into_owned(),
+        None => match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            Err(_) => WINDOWS_1252.decode(bytes).0.into_owned(),
+        },
+    }
+}
+
+/// Fetches HTML content from a URL using the shared client (with retries)
 pub async fn fetch_html(url: &str) -> Result<String, Box<dyn Error>> {
-    let response = reqwest::get(url).await.map_err(|e| {
-        eprintln!("Error: Failed to fetch URL: {}", url);
-        e
-    })?;
-    Ok(response.text().await?)
+    shared_client().fetch_html(url).await
+}
+
+// ============================================================================
+// NAME FORMATTING
+// ============================================================================
+
+/// Generational suffixes kept with the surname when reformatting names
+const NAME_SUFFIXES: &[&str] = &["Jr", "Sr", "II", "III", "IV"];
+
+fn is_name_suffix(token: &str) -> bool {
+    let token = token.trim_end_matches('.');
+    NAME_SUFFIXES.iter().any(|s| s.eq_ignore_ascii_case(token))
 }
 
-/// Checks if a string represents a disqualification status
+/// Splits a "Last, First" or "First Last" name into (first, last, suffix) parts
+fn split_name(name: &str) -> (Vec<&str>, Vec<&str>, Vec<&str>) {
+    let (last, first): (Vec<&str>, Vec<&str>) = match name.split_once(',') {
+        Some((last, first)) => (last.split_whitespace().collect(), first.split_whitespace().collect()),
+        None => {
+            let tokens: Vec<&str> = name.split_whitespace().collect();
+            let (rest, suffix_count) = match tokens.split_last() {
+                Some((last, rest)) if !rest.is_empty() && is_name_suffix(last) => (rest, 1),
+                _ => (&tokens[..], 0),
+            };
+            let mut last: Vec<&str> = rest.last().into_iter().copied().collect();
+            last.extend(&tokens[tokens.len() - suffix_count..]);
+            let first = rest[..rest.len().saturating_sub(1)].to_vec();
+            (last, first)
+        }
+    };
+
+    // Suffixes may sit on either side of the comma; a lone token is never a suffix
+    let mut suffix = Vec::new();
+    let mut strip = |tokens: Vec<&str>| -> Vec<&str> {
+        if tokens.len() < 2 {
+            return tokens;
+        }
+        tokens.into_iter()
+            .filter(|t| {
+                let is_suffix = is_name_suffix(t);
+                if is_suffix {
+                    suffix.push(*t);
+                }
+                !is_suffix
+            })
+            .collect()
+    };
+    let last = strip(last);
+    let first = strip(first);
+
+    (first, last, suffix)
+}
+
+/// Formats a name as "First Last Suffix" (e.g. "Bochenski, Grant" -> "Grant Bochenski")
+pub fn name_first_last(name: &str) -> String {
+    let (first, last, suffix) = split_name(name);
+    first.into_iter().chain(last).chain(suffix).collect::<Vec<_>>().join(" ")
+}
+
+/// Formats a name as "Last Suffix, First" (e.g. "Grant Bochenski" -> "Bochenski, Grant")
+pub fn name_last_first(name: &str) -> String {
+    let (first, last, suffix) = split_name(name);
+    let surname = last.into_iter().chain(suffix).collect::<Vec<_>>().join(" ");
+    if first.is_empty() {
+        surname
+    } else {
+        format!("{}, {}", surname, first.join(" "))
+    }
+}
+
+/// Parses a place token, returning the place and whether it is a tie ("3T" or "*3")
+pub fn parse_place(token: &str) -> Option<(u8, bool)> {
+    let number = token.strip_suffix(['T', 't'])
+        .or_else(|| token.strip_prefix('*'));
+    match number {
+        Some(number) => Some((number.parse().ok()?, true)),
+        None => Some((token.parse().ok()?, false)),
+    }
+}
+
+/// Checks if a string represents a disqualification or non-finish status
 pub fn is_dq_status(s: &str) -> bool {
-    matches!(s, "DQ" | "DSQ" | "DFS" | "DNS")
+    matches!(s, "DQ" | "DSQ" | "DFS" | "DNS" | "DNF")
+}
+
+/// Parses a reaction time in seconds from "r:+0.21", "r:-0.02", "r+0.65", or "0.65",
+/// keeping the sign (negative relay exchanges left the block early)
+pub fn parse_reaction_seconds(text: &str) -> Option<f64> {
+    let value = text.trim().trim_start_matches('r').trim_start_matches(':');
+    value.parse().ok()
+}
+
+/// Checks if a result line ends in a points column: up to three digits right after a
+/// time. A bare trailing number is otherwise part of the team or school name
+/// ("Club Wolverine 2", "Army West Point 2").
+pub fn has_points_column(parts: &[&str]) -> bool {
+    match parts {
+        [.., time, points] => {
+            (1..=3).contains(&points.len())
+                && points.chars().all(|c| c.is_ascii_digit())
+                && is_valid_time_format(time.trim_start_matches(['x', 'X']))
+        }
+        _ => false,
+    }
 }
 
 /// Checks if a string matches a year pattern; often age for club meets and grade for collegiate
@@ -39,39 +211,279 @@ pub fn is_year_pattern(s: &str) -> bool {
         || s.chars().all(|c| c.is_ascii_digit())
 }
 
-/// Validates a string as a swim time format (e.g., 21.09, 1:08.61, 4:02.31N)
+/// Validates a string as a swim time format: `[H:]MM:SS.hh`, `[M]M:SS.hh`, or `SS.hh` with an
+/// optional single trailing flag letter (e.g., 21.09, 1:08.61, 14:35.12, 4:02.31N)
 pub fn is_valid_time_format(s: &str) -> bool {
-    let s = s.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let s = s.strip_suffix(|c: char| c.is_ascii_alphabetic()).unwrap_or(s);
+    let all_digits = |t: &str| !t.is_empty() && t.chars().all(|c| c.is_ascii_digit());
 
-    if !s.contains(':') && !s.contains('.') {
+    let Some((whole, frac)) = s.split_once('.') else {
+        return false;
+    };
+    if frac.len() != 2 || !all_digits(frac) {
         return false;
     }
 
-    if let Some(colon_pos) = s.find(':') {
-        let before = &s[..colon_pos];
-        let after = &s[colon_pos + 1..];
-        return !before.is_empty()
-            && before.chars().all(|c| c.is_ascii_digit())
-            && after.contains('.')
-            && after.len() >= 4;
+    // Leading field has 1-2 digits; later minute/second fields are zero-padded and under 60
+    let fields: Vec<&str> = whole.split(':').collect();
+    fields.len() <= 3
+        && fields[0].len() <= 2
+        && all_digits(fields[0])
+        && fields[1..].iter().all(|f| f.len() == 2 && all_digits(f) && *f < "60")
+}
+
+/// Parses a swim time (see is_valid_time_format) to centiseconds, e.g. "14:35.12" -> 87512
+pub fn parse_time_to_centiseconds(s: &str) -> Option<u32> {
+    if !is_valid_time_format(s) {
+        return None;
     }
+    s.parse::<SwimTime>().ok().map(|t| t.centiseconds())
+}
 
-    if let Some(dot_pos) = s.find('.') {
-        let after = &s[dot_pos + 1..];
-        return after.len() >= 2 && after.chars().all(|c| c.is_ascii_digit());
+/// Session (round) of an event page, from the letter in its filename code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionType {
+    Prelims,
+    Finals,
+    SwimOff,
+    Unknown,
+}
+
+impl SessionType {
+    /// Maps a session letter (P/F/S) to a SessionType
+    pub fn from_char(c: char) -> SessionType {
+        match c.to_ascii_uppercase() {
+            'P' => SessionType::Prelims,
+            'F' => SessionType::Finals,
+            'S' => SessionType::SwimOff,
+            _ => SessionType::Unknown,
+        }
+    }
+
+    /// Returns the session letter used in results ('?' when unknown)
+    pub fn as_char(&self) -> char {
+        match self {
+            SessionType::Prelims => 'P',
+            SessionType::Finals => 'F',
+            SessionType::SwimOff => 'S',
+            SessionType::Unknown => '?',
+        }
+    }
+
+    /// Returns a display label for the session
+    pub fn label(&self) -> &'static str {
+        match self {
+            SessionType::Prelims => "Prelims",
+            SessionType::Finals => "Finals",
+            SessionType::SwimOff => "Swim-off",
+            SessionType::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Returns a display label for a session letter (e.g. 'P' -> "Prelims")
+pub fn session_label(session: char) -> &'static str {
+    SessionType::from_char(session).label()
+}
+
+/// Collects a DQ description that may wrap over several lines, starting at `lines[0]`
+/// and stopping at the first line `is_description` rejects. Returns the lines joined with
+/// a space and the number of lines consumed.
+pub fn take_dq_description(lines: &[&str], is_description: fn(&str) -> bool) -> (Option<String>, usize) {
+    let description: Vec<&str> = lines.iter()
+        .map(|line| line.trim())
+        .take_while(|line| is_description(line))
+        .collect();
+    let consumed = description.len();
+    if consumed == 0 {
+        (None, 0)
+    } else {
+        (Some(description.join(" ")), consumed)
+    }
+}
+
+/// Extracts the cumulative time from a split token, dropping an attached
+/// parenthesized interval (e.g. "1:02.33(31.22)" -> "1:02.33")
+pub fn cumulative_split_time(token: &str) -> Option<&str> {
+    let time = match token.split_once('(') {
+        Some((before, _)) => before,
+        None => token,
+    };
+
+    let is_time = time.chars().next().is_some_and(|c| c.is_ascii_digit())
+        && is_valid_time_format(time);
+    is_time.then_some(time)
+}
+
+/// Extracts the session from an event URL filename (e.g. 240327F003.htm -> Finals)
+///
+/// Ignores query strings, fragments, and extension case, and looks for the last
+/// P/F/S letter followed by a 3-digit event number.
+pub fn extract_session_from_url(url: &str) -> SessionType {
+    event_code_from_url(url).map_or(SessionType::Unknown, |(session, _)| SessionType::from_char(session))
+}
+
+/// Reads the session letter (uppercased) and event number from an event URL filename,
+/// as extract_session_from_url does (e.g. 240327f003.HTM?x=1 -> ('F', 3))
+pub(crate) fn event_code_from_url(url: &str) -> Option<(char, u32)> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let filename = path.trim_end_matches('/').rsplit('/').next().unwrap_or(path);
+
+    let lower = filename.to_ascii_lowercase();
+    let code = if lower.ends_with(".html") {
+        &filename[..filename.len() - 5]
+    } else if lower.ends_with(".htm") {
+        &filename[..filename.len() - 4]
+    } else {
+        filename
+    };
+
+    let chars: Vec<char> = code.chars().collect();
+    for i in (0..chars.len()).rev() {
+        let c = chars[i].to_ascii_uppercase();
+        if !matches!(c, 'P' | 'F' | 'S') || i + 4 > chars.len() {
+            continue;
+        }
+        let number_follows = chars[i + 1..i + 4].iter().all(|c| c.is_ascii_digit());
+        let number_ends = chars.get(i + 4).is_none_or(|c| !c.is_ascii_digit());
+        if number_follows && number_ends {
+            let number = chars[i + 1..i + 4].iter().collect::<String>().parse().ok()?;
+            return Some((c, number));
+        }
     }
 
-    false
+    None
 }
 
-/// Extracts session character (P/F) from an event URL filename
-pub fn extract_session_from_url(url: &str) -> Option<char> {
-    let filename = url.rsplit('/').next()?;
-    let code = filename.trim_end_matches(".htm");
-    let session = code.chars().rev().nth(3)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_place_reads_ties() {
+        assert_eq!(parse_place("3"), Some((3, false)));
+        assert_eq!(parse_place("3T"), Some((3, true)));
+        assert_eq!(parse_place("3t"), Some((3, true)));
+        assert_eq!(parse_place("*3"), Some((3, true)));
+        assert_eq!(parse_place("--"), None);
+        assert_eq!(parse_place("T"), None);
+        assert_eq!(parse_place("*"), None);
+    }
+
+    #[test]
+    fn sanitize_name_replaces_slashes_and_unsafe_chars() {
+        assert_eq!(sanitize_name("Women 4x50 Free/Medley Relay"), "Women_4x50_Free_Medley_Relay");
+        assert_eq!(sanitize_name("Event 1 / Heat 2"), "Event_1_Heat_2");
+        assert_eq!(sanitize_name("A\\B: C*?\"<>|D"), "A_B_C_D");
+        assert_eq!(sanitize_name("  ..Trimmed.. "), "Trimmed");
+    }
+
+    #[test]
+    fn sanitize_name_normalizes_accents_to_nfc() {
+        let composed = "Jos\u{e9} N\u{fa}\u{f1}ez";
+        let decomposed = "Jose\u{301} Nu\u{301}n\u{303}ez";
+        assert_eq!(sanitize_name(composed), "Jos\u{e9}_N\u{fa}\u{f1}ez");
+        assert_eq!(sanitize_name(decomposed), sanitize_name(composed));
+    }
+
+    #[test]
+    fn sanitize_name_caps_long_names_with_hash() {
+        let exact = "a".repeat(MAX_NAME_BYTES);
+        assert_eq!(sanitize_name(&exact), exact);
+
+        let long_a = format!("{} Championships A", "Big Ten Conference ".repeat(8));
+        let long_b = format!("{} Championships B", "Big Ten Conference ".repeat(8));
+        let (a, b) = (sanitize_name(&long_a), sanitize_name(&long_b));
+        assert!(a.len() <= MAX_NAME_BYTES);
+        assert_ne!(a, b);
+        let (prefix, hash) = a.rsplit_once('_').unwrap();
+        assert!(prefix.starts_with("Big_Ten_Conference_"));
+        assert_eq!(hash.len(), 8);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn sanitize_name_truncates_on_char_boundaries() {
+        // Two- and three-byte characters put the byte cap inside a character
+        for name in ["\u{e9}".repeat(80), "\u{20ac}".repeat(50), format!("a{}", "\u{e9}".repeat(80))] {
+            let sanitized = sanitize_name(&name);
+            assert!(sanitized.len() <= MAX_NAME_BYTES, "{} bytes", sanitized.len());
+            let (prefix, hash) = sanitized.rsplit_once('_').unwrap();
+            assert!(name.starts_with(prefix));
+            assert_eq!(hash.len(), 8);
+        }
+    }
+
+    #[test]
+    fn session_read_from_url_variants() {
+        for (url, session) in [
+            ("https://results.example.com/meet/240327F003.htm", SessionType::Finals),
+            ("https://results.example.com/meet/240327P003.htm?x=1", SessionType::Prelims),
+            ("https://results.example.com/meet/240327F003.HTM", SessionType::Finals),
+            ("https://results.example.com/meet/240327f003.htm", SessionType::Finals),
+            ("https://results.example.com/meet/240327S012.htm#top", SessionType::SwimOff),
+            ("https://results.example.com/meet/240327F003_2.htm", SessionType::Finals),
+            ("https://results.example.com/meet/240327P003.htm/", SessionType::Prelims),
+            ("https://results.example.com/meet/evtindex.htm", SessionType::Unknown),
+            ("https://results.example.com/meet/", SessionType::Unknown),
+            ("https://results.example.com/meet/240327F0031.htm", SessionType::Unknown),
+        ] {
+            assert_eq!(extract_session_from_url(url), session, "{}", url);
+        }
+    }
+
+    #[test]
+    fn distance_times_past_ten_minutes_are_valid() {
+        for time in ["14:35.12", "9:58.04", "10:00.00", "1:02:03.45", "4:02.31N"] {
+            assert!(is_valid_time_format(time), "{}", time);
+        }
+        for time in ["14:35.1", "9:60.04", "123:00.00", "14:5.12", "14:35"] {
+            assert!(!is_valid_time_format(time), "{}", time);
+        }
+        assert_eq!(parse_time_to_centiseconds("14:35.12"), Some(87512));
+        assert_eq!(parse_time_to_centiseconds("9:58.04"), Some(59804));
+        assert_eq!(parse_time_to_centiseconds("9:60.04"), None);
+    }
+
+    #[test]
+    fn trailing_team_number_is_not_a_points_column() {
+        let split = |line: &'static str| line.split_whitespace().collect::<Vec<_>>();
+        assert!(!has_points_column(&split("1 Smith, Jane 15 Club Wolverine 2 1:05.00 1:04.10")));
+        assert!(has_points_column(&split("1 Smith, Jane 15 Club Wolverine 2 1:05.00 1:04.10 20")));
+        assert!(!has_points_column(&split("3 Unattached 1 'A' 4:01.00 3:59.20")));
+        assert!(has_points_column(&split("3 Unattached 1 'A' 4:01.00 3:59.20 32")));
+        assert!(has_points_column(&split("9 Lee, Ann 16 Unattached 1 1:10.00 x1:09.50 0")));
+        assert!(!has_points_column(&split("9 Lee, Ann 16 Unattached 1 1:10.00 DQ")));
+    }
+
+    /// Results page with a Windows-1252 "ü" (0xFC) in a swimmer's name, after `head`
+    fn windows_1252_page(head: &str) -> Vec<u8> {
+        let mut bytes = format!("<html><head>{}</head><body><pre>\n", head).into_bytes();
+        bytes.extend_from_slice(b"  1 M\xfcller, Anna         SR Stanford          1:58.00    1:56.10\n");
+        bytes.extend_from_slice(b"</pre></body></html>");
+        bytes
+    }
+
+    #[test]
+    fn windows_1252_names_decode_with_or_without_a_charset() {
+        let undeclared = decode_html(&windows_1252_page(""), None);
+        let from_header = decode_html(&windows_1252_page(""), Some("text/html; charset=windows-1252"));
+        let from_meta = decode_html(&windows_1252_page("<meta charset=\"iso-8859-1\">"), None);
+        let from_http_equiv = decode_html(
+            &windows_1252_page("<meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1252\">"),
+            None,
+        );
+
+        for html in [&undeclared, &from_header, &from_meta, &from_http_equiv] {
+            assert!(html.contains("Müller, Anna"), "{}", html);
+            let results = crate::event_handler::parse_individual_event_html(
+                html, "Women 200 Yard Freestyle", 'F', None, None,
+            ).unwrap();
+            assert_eq!(results.swimmers[0].name, "Müller, Anna");
+        }
 
-    match session {
-        'P' | 'F' => Some(session),
-        _ => None,
+        // A declared UTF-8 page is not re-read as Windows-1252
+        let utf8 = "<html><head><meta charset=\"utf-8\"></head><body>Müller</body></html>";
+        assert!(decode_html(utf8.as_bytes(), Some("text/html; charset=utf-8")).contains("Müller"));
     }
 }
@@ -0,0 +1,150 @@
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::Path;
+
+use crate::output::{
+    individual_header, individual_row, max_individual_splits, max_relay_splits, metadata_row, relay_header,
+    output_swimmers, output_teams, relay_row, OutputOptions, METADATA_HEADER,
+};
+use crate::utils::sanitize_name;
+use crate::ParsedResults;
+
+/// Excel's limit on worksheet name length
+const MAX_SHEET_NAME_LEN: usize = 31;
+const SUMMARY_SHEET: &str = "Summary";
+const METADATA_SHEET: &str = "Metadata";
+
+// ============================================================================
+// SHEET NAMING
+// ============================================================================
+
+/// Returns a unique, Excel-safe sheet name like "E3_F" for an event
+fn sheet_name(event_number: Option<u32>, session: char, used: &mut HashSet<String>) -> String {
+    let base = match event_number {
+        Some(number) => format!("E{}_{}", number, session),
+        None => format!("E_{}", session),
+    };
+    let base: String = sanitize_name(&base).chars().take(MAX_SHEET_NAME_LEN - 3).collect();
+
+    let mut name = base.clone();
+    let mut counter = 2;
+    while used.contains(&name.to_lowercase()) {
+        name = format!("{}_{}", base, counter);
+        counter += 1;
+    }
+    used.insert(name.to_lowercase());
+    name
+}
+
+// ============================================================================
+// XLSX OUTPUT
+// ============================================================================
+
+/// Writes a workbook with one worksheet per event (same columns as the CSVs), a
+/// summary sheet linking to each event, and a metadata sheet unless metadata is disabled
+pub fn write_results_xlsx(
+    results: &ParsedResults,
+    options: &OutputOptions,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+
+    workbook.add_worksheet().set_name(SUMMARY_SHEET)?;
+
+    let mut used_names: HashSet<String> = HashSet::from([SUMMARY_SHEET.to_lowercase(), METADATA_SHEET.to_lowercase()]);
+    // (sheet name, event name, session, rows written)
+    let mut summary: Vec<(String, String, char, usize)> = Vec::new();
+
+    for event in &results.individual_results {
+        let max_splits = max_individual_splits(&[event], options);
+        let rows: Vec<Vec<String>> = output_swimmers(event, options).into_iter()
+            .map(|s| individual_row(event, s, options, max_splits))
+            .collect();
+
+        let number = event.race_info.as_ref().map(|i| i.event_number);
+        let name = sheet_name(number, event.session, &mut used_names);
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(&name)?;
+        write_table(sheet, &individual_header(options, max_splits), &rows, &header_format)?;
+        summary.push((name, event.event_name.clone(), event.session, rows.len()));
+    }
+
+    for event in &results.relay_results {
+        let max_splits = max_relay_splits(&[event], options);
+        let rows: Vec<Vec<String>> = output_teams(event, options).into_iter()
+            .map(|t| relay_row(event, t, options, max_splits))
+            .collect();
+
+        let number = event.race_info.as_ref().map(|i| i.event_number);
+        let name = sheet_name(number, event.session, &mut used_names);
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(&name)?;
+        write_table(sheet, &relay_header(options, max_splits), &rows, &header_format)?;
+        summary.push((name, event.event_name.clone(), event.session, rows.len()));
+    }
+
+    let sheet = workbook.worksheet_from_name(SUMMARY_SHEET)?;
+    let header = ["sheet", "event_name", "session", "entries"].map(String::from);
+    write_table(sheet, &header, &[], &header_format)?;
+    if let Some(ref title) = results.meet_title {
+        sheet.write_string(0, 5, title)?;
+    }
+    for (i, (name, event_name, session, count)) in summary.iter().enumerate() {
+        let row = i as u32 + 1;
+        sheet.write_url_with_text(row, 0, format!("internal:'{}'!A1", name).as_str(), name)?;
+        sheet.write_string(row, 1, event_name)?;
+        sheet.write_string(row, 2, session.to_string())?;
+        sheet.write_number(row, 3, *count as f64)?;
+    }
+
+    if options.metadata {
+        let rows: Vec<Vec<String>> = results.individual_results.iter()
+            .map(|e| metadata_row(&e.event_name, e.session, e.metadata.as_ref()).to_vec())
+            .chain(results.relay_results.iter()
+                .map(|e| metadata_row(&e.event_name, e.session, e.metadata.as_ref()).to_vec()))
+            .collect();
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(METADATA_SHEET)?;
+        write_table(sheet, &METADATA_HEADER.map(String::from), &rows, &header_format)?;
+    }
+
+    workbook.save(path)?;
+    log::info!("Results written to {}", path.display());
+    Ok(())
+}
+
+/// Writes a bold, frozen header row followed by rows. Numeric cells are written as numbers,
+/// except time and split columns, which stay text so "58.10" keeps its exact format.
+fn write_table(
+    sheet: &mut Worksheet,
+    header: &[String],
+    rows: &[Vec<String>],
+    header_format: &Format,
+) -> Result<(), Box<dyn Error>> {
+    for (col, title) in header.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, title, header_format)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+
+    let is_text: Vec<bool> = header.iter()
+        .map(|h| h.ends_with("_time") || h.ends_with("_reaction") || h.ends_with("_leg")
+            || ((h.starts_with("split") || h.contains("_split")) && !h.ends_with("_seconds")))
+        .collect();
+
+    for (i, row) in rows.iter().enumerate() {
+        let row_idx = i as u32 + 1;
+        for (col, value) in row.iter().enumerate() {
+            if is_text.get(col).copied().unwrap_or(false) {
+                sheet.write_string(row_idx, col as u16, value)?;
+                continue;
+            }
+            match value.parse::<f64>() {
+                Ok(number) if number.is_finite() => sheet.write_number(row_idx, col as u16, number)?,
+                _ => sheet.write_string(row_idx, col as u16, value)?,
+            };
+        }
+    }
+    Ok(())
+}